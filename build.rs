@@ -8,6 +8,11 @@ fn main() {
         println!("cargo:rustc-env=CONTRACT_ADDRESS={}", contract_addr);
     }
 
+    // Read DEPLOYMENT_BLOCK, the first block to query contract logs from
+    if let Ok(deployment_block) = env::var("DEPLOYMENT_BLOCK") {
+        println!("cargo:rustc-env=DEPLOYMENT_BLOCK={}", deployment_block);
+    }
+
     // Rebuild if .env changes
     println!("cargo:rerun-if-changed=.env");
 }
\ No newline at end of file
@@ -0,0 +1,160 @@
+use std::collections::HashSet;
+
+use web3::types::{Address, H256};
+
+use crate::{
+    address_book::shorten,
+    chain,
+    evm::types::{MinimumPriceChange, NftTransfer},
+};
+
+/// Kinds of activity, read from NFT transfer events except for changes of the minimum bid, which are read from
+/// past state and the `setMinimumBid` calls behind them.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ActivityKind {
+    BidPlaced,
+    BidRemoved,
+    ChildMinted,
+    Burned,
+    MinimumPriceChanged,
+    OwnershipTransferred,
+}
+
+impl ActivityKind {
+    pub const ALL: [Self; 6] = [
+        Self::BidPlaced,
+        Self::BidRemoved,
+        Self::ChildMinted,
+        Self::Burned,
+        Self::MinimumPriceChanged,
+        Self::OwnershipTransferred,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::BidPlaced => "Bids placed",
+            Self::BidRemoved => "Bids removed",
+            Self::ChildMinted => "Children minted",
+            Self::Burned => "Burns",
+            Self::MinimumPriceChanged => "Minimum bid changes",
+            Self::OwnershipTransferred => "Transfers",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Activity {
+    pub kind: ActivityKind,
+    pub tx_hash: H256,
+    pub block_number: u64,
+    /// Seconds since the epoch, `None` until the block has been loaded.
+    pub timestamp: Option<u64>,
+    pub description: String,
+    pub address: Address,
+}
+
+pub fn transaction_link(tx_hash: &H256) -> Option<String> {
+    chain::active()
+        .block_explorer_urls
+        .map(|[url]| format!("{}/tx/{:?}", url.trim_end_matches('/'), tx_hash))
+}
+
+/// Blocks right before the burns of tokens that are neither `token_id` nor one of `bids` and `children`. The state
+/// of those blocks tells whether a burned token was a bid or a child of `token_id`.
+pub fn unattributed_burns(
+    token_id: u128,
+    bids: &HashSet<u128>,
+    children: &HashSet<u128>,
+    transfers: &[NftTransfer],
+) -> HashSet<u64> {
+    transfers
+        .iter()
+        .filter(|transfer| {
+            transfer.to.is_zero()
+                && transfer.token_id != token_id
+                && !bids.contains(&transfer.token_id)
+                && !children.contains(&transfer.token_id)
+        })
+        .map(|transfer| transfer.block_number.saturating_sub(1))
+        .collect()
+}
+
+/// Activity around a token, newest first. `bids` and `children` are every bid and child the token is known to have
+/// had, a bid that became a child when it was approved can be in both.
+pub fn timeline(
+    token_id: u128,
+    bids: &HashSet<u128>,
+    children: &HashSet<u128>,
+    transfers: &[NftTransfer],
+) -> Vec<Activity> {
+    let mut activities = transfers
+        .iter()
+        .filter_map(|transfer| {
+            let minted = transfer.from.is_zero();
+            let burned = transfer.to.is_zero();
+            let id = transfer.token_id;
+            let (kind, description, address) = if id == token_id {
+                if burned {
+                    (
+                        ActivityKind::Burned,
+                        String::from("This NFT was burned"),
+                        transfer.operator,
+                    )
+                } else if !minted {
+                    (
+                        ActivityKind::OwnershipTransferred,
+                        format!("Transferred from {}", shorten(&transfer.from)),
+                        transfer.to,
+                    )
+                } else {
+                    return None;
+                }
+            } else if bids.contains(&id) || children.contains(&id) {
+                match (minted, burned) {
+                    (true, _) if bids.contains(&id) => {
+                        (ActivityKind::BidPlaced, format!("Bid {} was placed", id), transfer.to)
+                    }
+                    (true, _) => (ActivityKind::ChildMinted, format!("NFT {} was minted", id), transfer.to),
+                    (_, true) if children.contains(&id) => (
+                        ActivityKind::Burned,
+                        format!("NFT {} was burned", id),
+                        transfer.operator,
+                    ),
+                    (_, true) => (
+                        ActivityKind::BidRemoved,
+                        format!("Bid {} was withdrawn", id),
+                        transfer.operator,
+                    ),
+                    _ => return None,
+                }
+            } else {
+                return None;
+            };
+            Some(Activity {
+                kind,
+                tx_hash: transfer.tx_hash,
+                block_number: transfer.block_number,
+                timestamp: None,
+                description,
+                address,
+            })
+        })
+        .collect::<Vec<_>>();
+    activities.reverse();
+    activities
+}
+
+/// `activities` with the changes of the minimum bid added, newest first.
+pub fn with_minimum_price_changes(mut activities: Vec<Activity>, changes: &[MinimumPriceChange]) -> Vec<Activity> {
+    activities.extend(changes.iter().map(|change| Activity {
+        kind: ActivityKind::MinimumPriceChanged,
+        tx_hash: change.tx_hash,
+        block_number: change.block_number,
+        timestamp: None,
+        description: format!("Minimum bid set to {} OM", change.minimum_price),
+        address: change.sender,
+    }));
+    // stable, so activities of the same block keep their order
+    activities.sort_by(|a, b| b.block_number.cmp(&a.block_number));
+    activities
+}
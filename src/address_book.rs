@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use web3::types::Address;
+
+use crate::chain;
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Contact {
+    pub label: String,
+    pub color: String,
+    pub notes: String,
+}
+
+pub type AddressBook = HashMap<Address, Contact>;
+
+/// EIP-55 mixed-case checksum encoding of an address.
+pub fn to_checksum(address: &Address) -> String {
+    let hex = hex_lower(address);
+    let hash = web3::signing::keccak256(hex.as_bytes());
+    let checksummed = hex
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> if i % 2 == 0 { 4 } else { 0 }) & 0x0f;
+            if nibble >= 8 { c.to_ascii_uppercase() } else { c }
+        })
+        .collect::<String>();
+    format!("0x{}", checksummed)
+}
+
+fn hex_lower(address: &Address) -> String {
+    address.as_bytes().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn shorten(address: &Address) -> String {
+    let checksum = to_checksum(address);
+    format!("{}…{}", &checksum[..6], &checksum[checksum.len() - 4..])
+}
+
+/// Label of the address if it is in the address book, or its shortened checksum otherwise.
+pub fn display_name(address_book: &AddressBook, address: &Address) -> String {
+    address_book
+        .get(address)
+        .filter(|contact| !contact.label.is_empty())
+        .map(|contact| contact.label.clone())
+        .unwrap_or_else(|| shorten(address))
+}
+
+pub fn explorer_link(address: &Address) -> Option<String> {
+    chain::active()
+        .block_explorer_urls
+        .map(|[url]| format!("{}/address/{}", url.trim_end_matches('/'), to_checksum(address)))
+}
+
+pub fn export(address_book: &AddressBook) -> String {
+    serde_json::to_string_pretty(address_book).unwrap()
+}
+
+pub fn import(json: &str) -> Option<AddressBook> {
+    serde_json::from_str(json).ok()
+}
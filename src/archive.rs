@@ -0,0 +1,179 @@
+//! Bundling files into a ZIP archive, so that a set of files downloads as one.
+//!
+//! Files are stored without compression, which PNG images don't benefit from anyway, and without the ZIP64
+//! extensions, so an archive holds up to [`MAX_FILES`] files and 4 GiB. The archive is written file by file, so that
+//! the contents don't have to be held until the end.
+
+use crate::util::crc32;
+
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+const CENTRAL_HEADER: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+const LOCAL_HEADER_SIZE: u64 = 30;
+const CENTRAL_HEADER_SIZE: u64 = 46;
+const END_OF_CENTRAL_DIRECTORY_SIZE: u64 = 22;
+// 2.0, the first version with folders
+const VERSION: u16 = 20;
+// file names are UTF-8
+const UTF8_FLAG: u16 = 1 << 11;
+
+pub const MAX_FILES: usize = u16::MAX as usize;
+// every size and offset in the archive is 32 bits
+const MAX_SIZE: u64 = u32::MAX as u64;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    TooManyFiles,
+    TooLarge,
+}
+
+/// Writes an archive, keeping only its central directory. The parts returned by [`ZipWriter::add`] followed by the
+/// one of [`ZipWriter::finish`] make up the archive.
+#[derive(Default)]
+pub struct ZipWriter {
+    written: u64,
+    files: usize,
+    directory: Vec<u8>,
+}
+
+impl ZipWriter {
+    /// The local header and contents of a file. Refused when the archive, with its central directory, would no
+    /// longer fit into the format, in which case the archive is left as it was.
+    pub fn add(&mut self, name: &str, contents: &[u8]) -> Result<Vec<u8>, Error> {
+        if self.files >= MAX_FILES {
+            return Err(Error::TooManyFiles);
+        }
+        let name_length = name.len() as u64;
+        let local_size = LOCAL_HEADER_SIZE + name_length + contents.len() as u64;
+        let directory_size = self.directory.len() as u64 + CENTRAL_HEADER_SIZE + name_length;
+        if name_length > u16::MAX as u64
+            || self.written + local_size + directory_size + END_OF_CENTRAL_DIRECTORY_SIZE > MAX_SIZE
+        {
+            return Err(Error::TooLarge);
+        }
+        let offset = self.written as u32;
+        let crc = crc32(contents);
+        let size = contents.len() as u32;
+
+        let mut part = Vec::with_capacity(local_size as usize);
+        part.extend(LOCAL_HEADER.to_le_bytes());
+        part.extend(VERSION.to_le_bytes());
+        part.extend(UTF8_FLAG.to_le_bytes());
+        // stored, at no particular time
+        part.extend([0; 6]);
+        part.extend(crc.to_le_bytes());
+        part.extend(size.to_le_bytes());
+        part.extend(size.to_le_bytes());
+        part.extend((name.len() as u16).to_le_bytes());
+        part.extend(0u16.to_le_bytes());
+        part.extend(name.as_bytes());
+        part.extend(contents);
+
+        self.directory.extend(CENTRAL_HEADER.to_le_bytes());
+        self.directory.extend(VERSION.to_le_bytes());
+        self.directory.extend(VERSION.to_le_bytes());
+        self.directory.extend(UTF8_FLAG.to_le_bytes());
+        self.directory.extend([0; 6]);
+        self.directory.extend(crc.to_le_bytes());
+        self.directory.extend(size.to_le_bytes());
+        self.directory.extend(size.to_le_bytes());
+        self.directory.extend((name.len() as u16).to_le_bytes());
+        // no extra field, comment, disk number or attributes
+        self.directory.extend([0; 12]);
+        self.directory.extend(offset.to_le_bytes());
+        self.directory.extend(name.as_bytes());
+
+        self.written += local_size;
+        self.files += 1;
+        Ok(part)
+    }
+
+    /// The central directory and its end record, which close the archive.
+    pub fn finish(self) -> Vec<u8> {
+        let mut part = self.directory;
+        let directory_size = part.len() as u32;
+        let files = self.files as u16;
+        part.extend(END_OF_CENTRAL_DIRECTORY.to_le_bytes());
+        part.extend([0; 4]);
+        part.extend(files.to_le_bytes());
+        part.extend(files.to_le_bytes());
+        part.extend(directory_size.to_le_bytes());
+        part.extend((self.written as u32).to_le_bytes());
+        part.extend(0u16.to_le_bytes());
+        part
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zip(files: &[(String, Vec<u8>)]) -> Vec<u8> {
+        let mut writer = ZipWriter::default();
+        let mut archive = Vec::new();
+        for (name, contents) in files {
+            archive.extend(writer.add(name, contents).unwrap());
+        }
+        archive.extend(writer.finish());
+        archive
+    }
+
+    fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+        u16::from_le_bytes(bytes[offset..offset + 2].try_into().unwrap())
+    }
+
+    fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn central_directory_points_at_each_file() {
+        let files = vec![
+            ("a.png".to_string(), b"first".to_vec()),
+            ("b.png".to_string(), b"second file".to_vec()),
+        ];
+        let archive = zip(&files);
+
+        let end = archive.len() - 22;
+        assert_eq!(u32_at(&archive, end), END_OF_CENTRAL_DIRECTORY);
+        assert_eq!(u16_at(&archive, end + 10), 2);
+        let mut entry = u32_at(&archive, end + 16) as usize;
+        for (name, contents) in &files {
+            assert_eq!(u32_at(&archive, entry), CENTRAL_HEADER);
+            assert_eq!(u32_at(&archive, entry + 16), crc32(contents));
+            let name_length = u16_at(&archive, entry + 28) as usize;
+            assert_eq!(&archive[entry + 46..entry + 46 + name_length], name.as_bytes());
+
+            let local = u32_at(&archive, entry + 42) as usize;
+            assert_eq!(u32_at(&archive, local), LOCAL_HEADER);
+            let data = local + 30 + u16_at(&archive, local + 26) as usize;
+            assert_eq!(&archive[data..data + contents.len()], contents.as_slice());
+            entry += 46 + name_length;
+        }
+    }
+
+    #[test]
+    fn empty_archive_is_only_the_end_record() {
+        let archive = zip(&[]);
+        assert_eq!(archive.len(), 22);
+        assert_eq!(u32_at(&archive, 0), END_OF_CENTRAL_DIRECTORY);
+    }
+
+    #[test]
+    fn files_past_the_limits_are_refused() {
+        let mut writer = ZipWriter::default();
+        for _ in 0..MAX_FILES {
+            writer.add("", &[]).unwrap();
+        }
+        assert_eq!(writer.add("", &[]), Err(Error::TooManyFiles));
+
+        let mut writer = ZipWriter {
+            written: MAX_SIZE - 200,
+            ..Default::default()
+        };
+        assert!(writer.add("a.png", &[0; 64]).is_ok());
+        assert_eq!(writer.add("b.png", &[0; 64]), Err(Error::TooLarge));
+        // the refused file left no trace
+        assert_eq!(writer.files, 1);
+    }
+}
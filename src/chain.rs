@@ -10,6 +10,11 @@ pub fn _ethereum() -> Chain {
     }
 }
 
+/// The chain the app is deployed on.
+pub fn active() -> Chain {
+    sepolia_testnet()
+}
+
 pub fn sepolia_testnet() -> Chain {
     Chain {
         chain_name: String::from("Sepolia test network"),
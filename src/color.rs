@@ -1,3 +1,6 @@
+use std::f64::consts::PI;
+
+use reactive_stores::Store;
 use serde::{Deserialize, Serialize};
 
 pub fn smoothstep(a: &[u8; 3], b: &[u8; 3], t: f64) -> [u8; 3] {
@@ -23,16 +26,16 @@ pub fn hex_to_rgb(hex: &str) -> Option<[u8; 3]> {
     Some([r, g, b])
 }
 
-#[derive(Clone, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Copy, Deserialize, PartialEq, Serialize, Store)]
 pub struct Wave {
-    bias: f32,
-    amplitude: f32,
-    frequency: f32,
-    phase: f32,
+    pub bias: f64,
+    pub amplitude: f64,
+    pub frequency: f64,
+    pub phase: f64,
 }
 
 impl Wave {
-    pub fn new(bias: f32, amplitude: f32, frequency: f32, phase: f32) -> Self {
+    pub fn new(bias: f64, amplitude: f64, frequency: f64, phase: f64) -> Self {
         Self {
             bias,
             amplitude,
@@ -42,16 +45,54 @@ impl Wave {
     }
 }
 
-#[derive(Clone, Deserialize, PartialEq, Serialize)]
+impl From<Wave> for mandelbrot_explorer::Wave {
+    fn from(value: Wave) -> Self {
+        Self::new(
+            value.bias as f32,
+            value.amplitude as f32,
+            value.frequency as f32,
+            value.phase as f32,
+        )
+    }
+}
+
+#[derive(Clone, Deserialize, PartialEq, Serialize, Store)]
 pub struct WaveGradient {
     pub red: Wave,
     pub green: Wave,
     pub blue: Wave,
 }
 
+impl Default for WaveGradient {
+    fn default() -> Self {
+        Self {
+            red: Wave::new(0.5, 0.7, 12.0, 0.0),
+            green: Wave::new(0.5, 0.7, 10.0, 1.5),
+            blue: Wave::new(0.5, 0.7, 8.0, 3.0),
+        }
+    }
+}
+
+impl WaveGradient {
+    /// Color at position `t` in [0, 1], following the same waves as the renderer.
+    pub fn color_at(&self, t: f64) -> [u8; 3] {
+        let t = (1.0 - t * 2.0).abs();
+        [
+            (self.red, f64::cos(t * self.red.frequency + self.red.phase)),
+            (self.green, f64::sin(t * self.green.frequency + self.green.phase)),
+            (self.blue, f64::cos(t * self.blue.frequency + self.blue.phase + 1.0)),
+        ]
+        .map(|(wave, trig)| ((wave.bias + wave.amplitude * trig).clamp(0.0, 1.0) * 255.0) as u8)
+    }
+}
+
 impl From<WaveGradient> for mandelbrot_explorer::WaveGradient {
-    fn from(_value: WaveGradient) -> Self {
-        todo!()
+    fn from(value: WaveGradient) -> Self {
+        Self {
+            red: value.red.into(),
+            green: value.green.into(),
+            blue: value.blue.into(),
+        }
     }
 }
 
@@ -86,47 +127,257 @@ impl From<Checkpoint> for mandelbrot_explorer::Checkpoint {
     }
 }
 
+fn srgb_to_linear(channel: u8) -> f64 {
+    let c = channel as f64 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(channel: f64) -> u8 {
+    let c = channel.clamp(0.0, 1.0);
+    let c = if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    (c * 255.0).round() as u8
+}
+
+fn rgb_to_oklab(color: &[u8; 3]) -> [f64; 3] {
+    let [r, g, b] = color.map(srgb_to_linear);
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+    [
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    ]
+}
+
+fn oklab_to_rgb([l, a, b]: [f64; 3]) -> [u8; 3] {
+    let l_ = (l + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+    let m_ = (l - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+    let s_ = (l - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+    [
+        4.0767416621 * l_ - 3.3077115913 * m_ + 0.2309699292 * s_,
+        -1.2684380046 * l_ + 2.6097574011 * m_ - 0.3413193965 * s_,
+        -0.0041960863 * l_ - 0.7034186147 * m_ + 1.7076147010 * s_,
+    ]
+    .map(linear_to_srgb)
+}
+
+fn rgb_to_hsv(color: &[u8; 3]) -> [f64; 3] {
+    let [r, g, b] = color.map(|channel| channel as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    [hue, if max == 0.0 { 0.0 } else { delta / max }, max]
+}
+
+fn hsv_to_rgb([hue, saturation, value]: [f64; 3]) -> [u8; 3] {
+    let c = value * saturation;
+    let x = c * (1.0 - ((hue / 60.0).rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match (hue.rem_euclid(360.0) / 60.0) as u8 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    [r, g, b].map(|channel| ((channel + value - c).clamp(0.0, 1.0) * 255.0).round() as u8)
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+/// Interpolate hues in degrees along the shorter way around the circle.
+fn lerp_hue(a: f64, b: f64, t: f64) -> f64 {
+    let delta = (b - a + 540.0).rem_euclid(360.0) - 180.0;
+    (a + delta * t).rem_euclid(360.0)
+}
+
+/// How colors are blended between two checkpoints.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub enum Interpolation {
+    Linear,
+    #[default]
+    Smoothstep,
+    Oklab,
+    Oklch,
+    Hsv,
+    Constant,
+}
+
+impl Interpolation {
+    pub const ALL: [Self; 6] = [
+        Self::Linear,
+        Self::Smoothstep,
+        Self::Oklab,
+        Self::Oklch,
+        Self::Hsv,
+        Self::Constant,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Linear => "Linear",
+            Self::Smoothstep => "Smoothstep",
+            Self::Oklab => "OKLab",
+            Self::Oklch => "OKLCH",
+            Self::Hsv => "HSV",
+            Self::Constant => "Constant",
+        }
+    }
+
+    pub fn interpolate(&self, a: &[u8; 3], b: &[u8; 3], t: f64) -> [u8; 3] {
+        match self {
+            Self::Linear => [0, 1, 2].map(|i| lerp(a[i] as f64, b[i] as f64, t).round() as u8),
+            Self::Smoothstep => smoothstep(a, b, t),
+            Self::Oklab => {
+                let (a, b) = (rgb_to_oklab(a), rgb_to_oklab(b));
+                oklab_to_rgb([0, 1, 2].map(|i| lerp(a[i], b[i], t)))
+            }
+            Self::Oklch => {
+                let polar = |[l, a, b]: [f64; 3]| [l, a.hypot(b), b.atan2(a).to_degrees()];
+                let (a, b) = (polar(rgb_to_oklab(a)), polar(rgb_to_oklab(b)));
+                let chroma = lerp(a[1], b[1], t);
+                let hue = lerp_hue(a[2], b[2], t).to_radians();
+                oklab_to_rgb([lerp(a[0], b[0], t), chroma * hue.cos(), chroma * hue.sin()])
+            }
+            Self::Hsv => {
+                let (a, b) = (rgb_to_hsv(a), rgb_to_hsv(b));
+                hsv_to_rgb([lerp_hue(a[0], b[0], t), lerp(a[1], b[1], t), lerp(a[2], b[2], t)])
+            }
+            Self::Constant => *a,
+        }
+    }
+}
+
 #[derive(Clone, Deserialize, PartialEq, Serialize)]
 pub struct StepGradient {
     pub checkpoints: Vec<Checkpoint>,
+    #[serde(default)]
+    pub interpolation: Interpolation,
 }
 
-impl Default for StepGradient {
-    fn default() -> Self {
+// The renderer only takes checkpoints, which it blends with a smoothstep, so other interpolations are handed to it
+// as this many samples
+const RENDER_SAMPLES: usize = 512;
+// Distance from a constant color's hold point to the next checkpoint. The renderer gets positions as f32, which
+// resolves about 6e-8 below 1, so the hold point has to stay well clear of the next checkpoint.
+const CONSTANT_HOLD_OFFSET: f64 = 1e-5;
+
+impl StepGradient {
+    pub fn new(checkpoints: Vec<Checkpoint>) -> Self {
         Self {
-            checkpoints: vec![
-                Checkpoint {
-                    position: 0.0,
-                    color: hex_to_rgb("#ff0000").unwrap(),
-                },
-                Checkpoint {
-                    position: 0.333,
-                    color: hex_to_rgb("#00ff00").unwrap(),
-                },
-                Checkpoint {
-                    position: 0.666,
-                    color: hex_to_rgb("#0000ff").unwrap(),
-                },
-            ],
+            checkpoints,
+            interpolation: Interpolation::default(),
+        }
+    }
+
+    fn sorted_checkpoints(&self) -> Vec<Checkpoint> {
+        let mut checkpoints = self.checkpoints.clone();
+        checkpoints.sort_by(|a, b| a.position.partial_cmp(&b.position).unwrap());
+        checkpoints
+    }
+
+    /// Color at position `t` in [0, 1). Past the last checkpoint the gradient wraps around to the first one.
+    pub fn color_at(&self, t: f64) -> [u8; 3] {
+        let checkpoints = self.sorted_checkpoints();
+        if checkpoints.is_empty() {
+            return [255, 255, 255];
+        }
+
+        let (a, b, local_t) = checkpoints
+            .windows(2)
+            .find(|pair| t >= pair[0].position && t < pair[1].position)
+            .map(|pair| {
+                (
+                    pair[0],
+                    pair[1],
+                    (t - pair[0].position) / (pair[1].position - pair[0].position),
+                )
+            })
+            .unwrap_or_else(|| {
+                let first = checkpoints[0];
+                let last = *checkpoints.last().unwrap();
+                let range = 1.0 - last.position + first.position;
+                (last, first, ((t - last.position + 1.0) % 1.0) / range)
+            });
+        self.interpolation.interpolate(&a.color, &b.color, local_t)
+    }
+
+    /// Checkpoints that look the same when blended with a smoothstep, as the renderer does.
+    fn render_checkpoints(&self) -> Vec<Checkpoint> {
+        match self.interpolation {
+            Interpolation::Smoothstep => self.checkpoints.clone(),
+            Interpolation::Constant => {
+                let checkpoints = self.sorted_checkpoints();
+                let mut steps = Vec::new();
+                for (index, checkpoint) in checkpoints.iter().enumerate() {
+                    let end = checkpoints
+                        .get(index + 1)
+                        .map_or(1.0 + checkpoints[0].position, |next| next.position);
+                    steps.push(*checkpoint);
+                    // hold the color until just before the next checkpoint
+                    steps.push(Checkpoint {
+                        position: (end - CONSTANT_HOLD_OFFSET).max(checkpoint.position) % 1.0,
+                        color: checkpoint.color,
+                    });
+                }
+                steps.sort_by(|a, b| a.position.partial_cmp(&b.position).unwrap());
+                steps
+            }
+            _ => (0..RENDER_SAMPLES)
+                .map(|index| {
+                    let position = index as f64 / RENDER_SAMPLES as f64;
+                    Checkpoint {
+                        position,
+                        color: self.color_at(position),
+                    }
+                })
+                .collect(),
         }
     }
 }
 
+impl Default for StepGradient {
+    fn default() -> Self {
+        Self::new(vec![
+            Checkpoint {
+                position: 0.0,
+                color: hex_to_rgb("#ff0000").unwrap(),
+            },
+            Checkpoint {
+                position: 0.333,
+                color: hex_to_rgb("#00ff00").unwrap(),
+            },
+            Checkpoint {
+                position: 0.666,
+                color: hex_to_rgb("#0000ff").unwrap(),
+            },
+        ])
+    }
+}
+
 impl From<StepGradient> for mandelbrot_explorer::StepGradient {
     fn from(value: StepGradient) -> Self {
         Self {
-            checkpoints: value
-                .checkpoints
-                .iter()
-                .map(|&Checkpoint { position, color }| mandelbrot_explorer::Checkpoint {
-                    position: position as f32,
-                    color: [
-                        color[0] as f32 / 255.0,
-                        color[1] as f32 / 255.0,
-                        color[2] as f32 / 255.0,
-                    ],
-                })
-                .collect(),
+            checkpoints: value.render_checkpoints().into_iter().map(Into::into).collect(),
         }
     }
 }
@@ -149,6 +400,42 @@ impl From<StepGradient> for Gradient {
     }
 }
 
+// Checkpoints of a gradient blended from two others
+const BLEND_SAMPLES: usize = 128;
+
+impl Gradient {
+    pub fn color_at(&self, t: f64) -> [u8; 3] {
+        match self {
+            Self::Wave(gradient) => gradient.color_at(t),
+            Self::Step(gradient) => gradient.color_at(t),
+        }
+    }
+
+    /// Gradient `t` of the way from this one to `other`, mixed in OKLab.
+    pub fn blend(&self, other: &Gradient, t: f64) -> Gradient {
+        if self == other || t <= 0.0 {
+            return self.clone();
+        }
+        if t >= 1.0 {
+            return other.clone();
+        }
+        let checkpoints = (0..BLEND_SAMPLES)
+            .map(|index| {
+                let position = index as f64 / BLEND_SAMPLES as f64;
+                Checkpoint {
+                    position,
+                    color: Interpolation::Oklab.interpolate(&self.color_at(position), &other.color_at(position), t),
+                }
+            })
+            .collect();
+        StepGradient {
+            checkpoints,
+            interpolation: Interpolation::Linear,
+        }
+        .into()
+    }
+}
+
 impl From<Gradient> for mandelbrot_explorer::Gradient {
     fn from(value: Gradient) -> Self {
         match value {
@@ -157,3 +444,101 @@ impl From<Gradient> for mandelbrot_explorer::Gradient {
         }
     }
 }
+
+/// A built-in palette with suggested rendering settings.
+pub struct Preset {
+    pub name: &'static str,
+    pub gradient: Gradient,
+    pub max_iterations: f64,
+    pub offset: f64,
+    pub length: f64,
+}
+
+fn step_gradient(colors: &[(f64, &str)]) -> Gradient {
+    StepGradient::new(
+        colors
+            .iter()
+            .map(|&(position, color)| Checkpoint {
+                position,
+                color: hex_to_rgb(color).unwrap(),
+            })
+            .collect(),
+    )
+    .into()
+}
+
+pub fn presets() -> Vec<Preset> {
+    vec![
+        Preset {
+            name: "Neon",
+            gradient: WaveGradient::default().into(),
+            max_iterations: 1600.0,
+            offset: 0.0,
+            length: 360.0,
+        },
+        Preset {
+            name: "Red velvet",
+            gradient: WaveGradient {
+                red: Wave::new(0.337, 0.662, 6.28, 0.0),
+                green: Wave::new(0.245, 0.586, 6.28, 0.0),
+                blue: Wave::new(0.334, -0.343, 6.28, 0.0),
+            }
+            .into(),
+            max_iterations: 1600.0,
+            offset: 0.0,
+            length: 360.0,
+        },
+        Preset {
+            name: "Evil rainbow",
+            gradient: WaveGradient {
+                red: Wave::new(0.4, 0.6, 7.2, 1.2),
+                green: Wave::new(0.4, 0.6, 5.9, -1.6),
+                blue: Wave::new(0.4, 0.6, 3.8, 2.1),
+            }
+            .into(),
+            max_iterations: 1600.0,
+            offset: 0.0,
+            length: 360.0,
+        },
+        Preset {
+            name: "Winter sunrise",
+            gradient: WaveGradient {
+                red: Wave::new(1.0, 0.5, 6.28, 0.9),
+                green: Wave::new(1.0, 0.5, 5.88, -PI),
+                blue: Wave::new(1.0, 0.5, PI, -3.64),
+            }
+            .into(),
+            max_iterations: 1600.0,
+            offset: 0.0,
+            length: 360.0,
+        },
+        Preset {
+            name: "Primaries",
+            gradient: StepGradient::default().into(),
+            max_iterations: 1600.0,
+            offset: 0.0,
+            length: 100.0,
+        },
+        Preset {
+            name: "Fire",
+            gradient: step_gradient(&[(0.0, "#000000"), (0.3, "#b01e00"), (0.6, "#ff9a00"), (0.85, "#fff3a0")]),
+            max_iterations: 2500.0,
+            offset: 0.0,
+            length: 200.0,
+        },
+        Preset {
+            name: "Ocean",
+            gradient: step_gradient(&[(0.0, "#001028"), (0.35, "#0a5ca8"), (0.7, "#5fd3e6"), (0.9, "#ffffff")]),
+            max_iterations: 2500.0,
+            offset: 0.0,
+            length: 200.0,
+        },
+        Preset {
+            name: "Monochrome",
+            gradient: step_gradient(&[(0.0, "#000000"), (0.5, "#ffffff")]),
+            max_iterations: 1600.0,
+            offset: 0.0,
+            length: 150.0,
+        },
+    ]
+}
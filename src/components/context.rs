@@ -8,9 +8,10 @@ use web3::transports::{Either, Http, eip_1193::Eip1193};
 
 use super::error_handler::ErrorHandler;
 use crate::{
-    chain::sepolia_testnet,
+    chain,
     context::{Context, State, StateStoreFields},
     evm::contracts::MandelbrotNFTContract,
+    util::store_item,
 };
 
 #[component]
@@ -23,7 +24,7 @@ pub fn ContextProvider(
     let transport = if let Some(ethereum) = &ethereum {
         Either::Left(Eip1193::new(ethereum.provider.clone()))
     } else {
-        Either::Right(Http::new(&sepolia_testnet().rpc_urls[0]).unwrap())
+        Either::Right(Http::new(&chain::active().rpc_urls[0]).unwrap())
     };
     let web3 = web3::Web3::new(transport);
 
@@ -55,6 +56,24 @@ pub fn ContextProvider(
         }
     });
 
+    Effect::new(move || store_item("frame_filters", &state.frame_filters().get()));
+    Effect::new(move || store_item("address_book", &state.address_book().get()));
+    Effect::new(move || store_item("watchlist", &state.watchlist().get()));
+    Effect::new(move || store_item("notifications", &state.notifications().get()));
+    Effect::new(move || store_item("browser_notifications", &state.browser_notifications().get()));
+    Effect::new(move || store_item("render_settings", &state.render_settings().get()));
+
+    Effect::new({
+        let context = context.clone();
+        move || {
+            state.watchlist().track();
+            let context = context.clone();
+            spawn_local(async move {
+                context.reload_watchlist().await;
+            });
+        }
+    });
+
     provide_context(error.write_only());
     provide_context(LocalStorage::wrap(context));
 
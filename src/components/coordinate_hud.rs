@@ -0,0 +1,215 @@
+use leptos::prelude::*;
+use leptos_use::use_interval_fn;
+use mandelbrot_explorer::{BigFloat, ISample};
+use send_wrapper::SendWrapper;
+
+use crate::{
+    context::{Context, StateStoreFields},
+    coordinates::{BASE_WIDTH, bounds_around, center, format_magnification, parse_decimal, to_decimal, width},
+};
+
+// how often the readout follows the view, in milliseconds
+const REFRESH_INTERVAL: u64 = 250;
+
+#[derive(Clone, Default, PartialEq)]
+struct Readout {
+    real: String,
+    imaginary: String,
+    width: String,
+    magnification: String,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Target {
+    Center,
+    Bounds,
+}
+
+#[component]
+fn Value(label: &'static str, value: Signal<String>) -> impl IntoView {
+    view! {
+        <div class="flex flex-col">
+            <span class="text-gray-400">{label}</span>
+            <span class="font-mono break-all select-all">{value}</span>
+        </div>
+    }
+}
+
+#[component]
+fn Input(label: &'static str, value: RwSignal<String>) -> impl IntoView {
+    view! {
+        <label class="flex flex-col gap-1">
+            <span class="text-gray-400">{label}</span>
+            <input
+                type="text"
+                prop:value=move || value.get()
+                on:input=move |ev| value.set(event_target_value(&ev))
+                class="px-2 py-1 rounded bg-gray-800 border border-gray-600 font-mono text-white"
+            />
+        </label>
+    }
+}
+
+#[component]
+pub fn CoordinateHud() -> impl IntoView {
+    let context = use_context::<SendWrapper<Context>>().unwrap();
+    let open = RwSignal::new(false);
+    let readout = RwSignal::new(Readout::default());
+    let current_token_id = context.state.current_token_id();
+
+    let go_to_open = RwSignal::new(false);
+    let target = RwSignal::new(Target::Center);
+    let real = RwSignal::new(String::new());
+    let imaginary = RwSignal::new(String::new());
+    let zoom = RwSignal::new(String::new());
+    let x_min = RwSignal::new(String::new());
+    let x_max = RwSignal::new(String::new());
+    let y_min = RwSignal::new(String::new());
+    let y_max = RwSignal::new(String::new());
+    let invalid = RwSignal::new(false);
+
+    use_interval_fn(
+        {
+            let context = context.clone();
+            move || {
+                if !open.get_untracked() {
+                    return;
+                }
+                let bounds = context.mandelbrot.lock().unwrap().engine.borrow().get_bounds();
+                let (x, y) = center(&bounds);
+                readout.set(Readout {
+                    real: to_decimal(&x),
+                    imaginary: to_decimal(&y),
+                    width: to_decimal(&width(&bounds)),
+                    magnification: format_magnification(&bounds, 3),
+                });
+            }
+        },
+        REFRESH_INTERVAL,
+    );
+
+    // start editing from the current view
+    let fill_in = {
+        let context = context.clone();
+        move || {
+            let bounds = context.mandelbrot.lock().unwrap().engine.borrow().get_bounds();
+            let (x, y) = center(&bounds);
+            real.set(to_decimal(&x));
+            imaginary.set(to_decimal(&y));
+            zoom.set(to_decimal(&(BigFloat::from(BASE_WIDTH) / width(&bounds))));
+            x_min.set(to_decimal(&bounds.x_min));
+            x_max.set(to_decimal(&bounds.x_max));
+            y_min.set(to_decimal(&bounds.y_min));
+            y_max.set(to_decimal(&bounds.y_max));
+            invalid.set(false);
+        }
+    };
+
+    let go_to = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let parse = |value: RwSignal<String>| value.with_untracked(|value| parse_decimal(value));
+        let bounds = match target.get_untracked() {
+            Target::Center => parse(real)
+                .zip(parse(imaginary))
+                .zip(parse(zoom).filter(|zoom| *zoom > BigFloat::from(0.0)))
+                .map(|((x, y), zoom)| bounds_around(&x, &y, zoom)),
+            Target::Bounds => (|| {
+                Some(mandelbrot_explorer::Bounds {
+                    x_min: parse(x_min)?,
+                    x_max: parse(x_max)?,
+                    y_min: parse(y_min)?,
+                    y_max: parse(y_max)?,
+                })
+            })(),
+        };
+        match bounds {
+            Some(bounds) if bounds.x_min < bounds.x_max && bounds.y_min < bounds.y_max => {
+                invalid.set(false);
+                context.mandelbrot.lock().unwrap().move_into_bounds(&bounds);
+            }
+            _ => invalid.set(true),
+        }
+    };
+
+    let tab_class = move |tab: Target| {
+        if target.get() == tab {
+            "px-2 py-1 bg-blue-600 rounded text-white"
+        } else {
+            "px-2 py-1 bg-gray-700 hover:bg-gray-600 rounded text-white"
+        }
+    };
+
+    view! {
+        <div class="fixed left-2 top-10 z-30 max-w-sm bg-gray-900/80 text-white text-xs rounded-md shadow-lg">
+            <button
+                on:click=move |_| open.update(|open| *open = !*open)
+                class="w-full px-3 py-1 text-left font-semibold hover:bg-gray-700 rounded-md"
+            >
+                {move || if open.get() { "Coordinates ▾" } else { "Coordinates ▸" }}
+            </button>
+            <Show when=move || open.get()>
+                <div class="flex flex-col gap-1 px-3 pb-2">
+                    <Value label="Real" value=Signal::derive(move || readout.with(|readout| readout.real.clone()))/>
+                    <Value label="Imaginary" value=Signal::derive(move || readout.with(|readout| readout.imaginary.clone()))/>
+                    <Value label="Width" value=Signal::derive(move || readout.with(|readout| readout.width.clone()))/>
+                    <Value
+                        label="Magnification"
+                        value=Signal::derive(move || readout.with(|readout| format!("{}×", readout.magnification)))
+                    />
+                    <Value
+                        label="NFT"
+                        value=Signal::derive(move || {
+                            current_token_id.get().map_or("None".to_string(), |token_id| format!("#{}", token_id))
+                        })
+                    />
+
+                    <button
+                        on:click={
+                            let fill_in = fill_in.clone();
+                            move |_| {
+                                if !go_to_open.get_untracked() {
+                                    fill_in();
+                                }
+                                go_to_open.update(|go_to_open| *go_to_open = !*go_to_open);
+                            }
+                        }
+                        class="mt-1 text-left font-semibold text-accent2 hover:underline"
+                    >
+                        {move || if go_to_open.get() { "Go to ▾" } else { "Go to ▸" }}
+                    </button>
+                    <Show when=move || go_to_open.get()>
+                        <form on:submit=go_to.clone() class="flex flex-col gap-2">
+                            <div class="flex gap-1">
+                                <button type="button" on:click=move |_| target.set(Target::Center) class=move || tab_class(Target::Center)>
+                                    "Center and zoom"
+                                </button>
+                                <button type="button" on:click=move |_| target.set(Target::Bounds) class=move || tab_class(Target::Bounds)>
+                                    "Bounds"
+                                </button>
+                            </div>
+                            <Show
+                                when=move || target.get() == Target::Center
+                                fallback=move || view! {
+                                    <Input label="Real minimum" value=x_min/>
+                                    <Input label="Real maximum" value=x_max/>
+                                    <Input label="Imaginary minimum" value=y_min/>
+                                    <Input label="Imaginary maximum" value=y_max/>
+                                }
+                            >
+                                <Input label="Real" value=real/>
+                                <Input label="Imaginary" value=imaginary/>
+                                <Input label="Magnification" value=zoom/>
+                            </Show>
+                            <Show when=move || invalid.get()>
+                                <span class="text-red-400">"Enter decimal numbers, with minimums below maximums"</span>
+                            </Show>
+                            <button type="submit" class="px-3 py-1 bg-blue-600 hover:bg-blue-500 rounded-md font-medium transition">
+                                "Go"
+                            </button>
+                        </form>
+                    </Show>
+                </div>
+            </Show>
+        </div>
+    }
+}
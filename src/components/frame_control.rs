@@ -2,14 +2,16 @@ use std::sync::Arc;
 
 use leptos::{prelude::*, task::spawn_local};
 use leptos_router::{
+    NavigateOptions,
     hooks::{use_navigate, use_query, use_query_map},
     params::Params,
 };
-use mandelbrot_explorer::Focus;
+use mandelbrot_explorer::{Focus, FrameColor};
 use send_wrapper::SendWrapper;
 
 use crate::{
-    context::{Context, ExplorerStoreFields, SalesStoreFields, StateStoreFields},
+    context::{Context, ExplorerStoreFields, StateStoreFields},
+    share::{self, Shared},
     util::preserve_log_level,
 };
 
@@ -18,16 +20,41 @@ struct FocusQuery {
     focus: Option<Focus>,
 }
 
+pub(super) fn is_bid_frame(color: &FrameColor) -> bool {
+    matches!(color, FrameColor::Yellow | FrameColor::Lemon | FrameColor::Green)
+}
+
 #[component]
 pub fn FrameControl() -> impl IntoView {
     let navigate = use_navigate();
     let query_map = use_query_map();
+    let shared = use_context::<Option<Result<Shared, share::Error>>>()
+        .flatten()
+        .and_then(Result::ok);
     let mut focus = use_query::<FocusQuery>()
         .get_untracked()
         .ok()
-        .and_then(|query| query.focus);
+        .and_then(|query| query.focus)
+        .or_else(|| shared.as_ref().and_then(|shared| shared.focus.parse().ok()));
     let context = use_context::<SendWrapper<Context>>().unwrap();
 
+    // a share link opens the NFT it was made in
+    if let Some(token_id) = shared.and_then(|shared| shared.token_id) {
+        let context = context.clone();
+        let navigate = navigate.clone();
+        Effect::new(move || {
+            if context.state.current_token_id().get_untracked() != Some(token_id) {
+                navigate(
+                    &preserve_log_level(format!("/tokens/{}", token_id), query_map),
+                    NavigateOptions {
+                        replace: true,
+                        ..Default::default()
+                    },
+                );
+            }
+        });
+    }
+
     // query tokens and bids
     Effect::new({
         let context = context.clone();
@@ -89,22 +116,7 @@ pub fn FrameControl() -> impl IntoView {
     // mark bid as selected on frame double click
     let select_bid = {
         let context = context.clone();
-        move |bid_id, selected| {
-            context.state.explorer().bids().update(|bids| {
-                if let Some(bid) = bids.get_mut(&bid_id) {
-                    bid.selected = selected;
-                }
-            });
-            context.state.sales().bids().update(|bids| {
-                for token_bids in bids.values_mut() {
-                    for bid in token_bids.values_mut() {
-                        if bid.token_id == bid_id {
-                            bid.selected = selected;
-                        }
-                    }
-                }
-            });
-        }
+        move |bid_id, selected| context.select_bid(bid_id, selected)
     };
 
     // handle frame double click and enter events
@@ -179,10 +191,11 @@ pub fn FrameControl() -> impl IntoView {
         }
     });
 
-    // update frames when explorer state changes
+    // update frames when explorer state or frame filters change
     Effect::new({
         let context = context.clone();
         move || {
+            let filters = context.state.frame_filters().get();
             let mandelbrot = &mut context.mandelbrot.lock().unwrap();
             let frames = &mut mandelbrot.frames;
             frames.clear();
@@ -193,6 +206,7 @@ pub fn FrameControl() -> impl IntoView {
                     .children()
                     .get()
                     .values()
+                    .filter(|token| filters.show_token(token))
                     .map(|token| token.to_frame(mandelbrot_explorer::FrameColor::Red)),
             );
             frames.extend(
@@ -202,18 +216,21 @@ pub fn FrameControl() -> impl IntoView {
                     .bids()
                     .get()
                     .values()
+                    .filter(|bid| filters.show_bid(bid))
                     .map(|token| token.to_frame(mandelbrot_explorer::FrameColor::Yellow)),
             );
-            frames.extend(
-                context
-                    .state
-                    .explorer()
-                    .nav_history()
-                    .get()
-                    .iter()
-                    .rev()
-                    .map(|token| token.to_frame(mandelbrot_explorer::FrameColor::Blue)),
-            );
+            if filters.ancestry {
+                frames.extend(
+                    context
+                        .state
+                        .explorer()
+                        .nav_history()
+                        .get()
+                        .iter()
+                        .rev()
+                        .map(|token| token.to_frame(mandelbrot_explorer::FrameColor::Blue)),
+                );
+            }
             if let Some(redraw) = &mandelbrot.redraw {
                 redraw();
             }
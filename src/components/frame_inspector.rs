@@ -0,0 +1,366 @@
+use leptos::{ev, prelude::*};
+use leptos_router::hooks::{use_navigate, use_query_map};
+use leptos_use::use_event_listener;
+use mandelbrot_explorer::{FrameColor, ISample};
+use send_wrapper::SendWrapper;
+use wasm_bindgen::JsCast;
+
+use super::frame_control::is_bid_frame;
+use crate::{
+    address_book::display_name,
+    context::{Context, ExplorerStoreFields, InventoryStoreFields, StateStoreFields, Tab},
+    coordinates::{contains, plane_point},
+    evm::types::Metadata,
+    util::preserve_log_level,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+enum FrameKind {
+    Token,
+    Bid,
+}
+
+#[derive(Clone)]
+struct Target {
+    kind: FrameKind,
+    token: Metadata,
+    x: i32,
+    y: i32,
+}
+
+/// Find the innermost frame under the cursor. Frames are ordered children, bids and then ancestry from the
+/// current token upwards, so the first frame containing the point is the most specific one.
+fn find_target(context: &Context, ev: &web_sys::MouseEvent) -> Option<Target> {
+    let canvas = ev.target()?.dyn_into::<web_sys::HtmlCanvasElement>().ok()?;
+    let (width, height) = (canvas.client_width() as f64, canvas.client_height() as f64);
+    if width <= 0.0 || height <= 0.0 {
+        return None;
+    }
+
+    let (id, is_bid) = {
+        let mandelbrot = context.mandelbrot.lock().unwrap();
+        let bounds = mandelbrot.engine.borrow().get_bounds();
+        let (x, y) = plane_point(&bounds, ev.offset_x() as f64 / width, ev.offset_y() as f64 / height);
+        mandelbrot
+            .frames
+            .iter()
+            .find(|frame| contains(&frame.bounds, &x, &y))
+            .map(|frame| (frame.id, is_bid_frame(&frame.color)))?
+    };
+
+    let explorer = context.state.explorer();
+    let (kind, token) = if is_bid {
+        (
+            FrameKind::Bid,
+            explorer.bids().with_untracked(|bids| bids.get(&id).cloned())?,
+        )
+    } else {
+        (
+            FrameKind::Token,
+            explorer
+                .children()
+                .with_untracked(|children| children.get(&id).cloned())
+                .or_else(|| {
+                    explorer
+                        .nav_history()
+                        .with_untracked(|nav_history| nav_history.iter().find(|token| token.token_id == id).cloned())
+                })?,
+        )
+    };
+
+    Some(Target {
+        kind,
+        token,
+        x: ev.client_x(),
+        y: ev.client_y(),
+    })
+}
+
+fn copy_to_clipboard(text: &str) {
+    let _ = web_sys::window().unwrap().navigator().clipboard().write_text(text);
+}
+
+/// Tooltip shown while hovering a frame and an action menu opened by right clicking one.
+#[component]
+pub fn FrameInspector() -> impl IntoView {
+    let context = use_context::<SendWrapper<Context>>().unwrap();
+    let canvas = use_context::<NodeRef<leptos::html::Canvas>>().unwrap();
+    let navigate = use_navigate();
+    let query_map = use_query_map();
+
+    let address_book = context.state.address_book();
+    let hovered = RwSignal::new(None::<Target>);
+    let menu = RwSignal::new(None::<Target>);
+    let editing = RwSignal::new(false);
+    let bids_minimum_price = RwSignal::new(0.0);
+
+    let close_menu = move || {
+        menu.set(None);
+        editing.set(false);
+    };
+
+    let _ = use_event_listener(canvas, ev::mousemove, {
+        let context = context.clone();
+        move |ev| {
+            if menu.with_untracked(Option::is_none) {
+                hovered.set(find_target(&context, &ev));
+            }
+        }
+    });
+    let _ = use_event_listener(canvas, ev::mouseleave, move |_| hovered.set(None));
+    let _ = use_event_listener(canvas, ev::contextmenu, {
+        let context = context.clone();
+        move |ev| {
+            if let Some(target) = find_target(&context, &ev) {
+                ev.prevent_default();
+                hovered.set(None);
+                editing.set(false);
+                bids_minimum_price.set(target.token.minimum_price);
+                menu.set(Some(target));
+            }
+        }
+    });
+
+    let zoom = {
+        let context = context.clone();
+        let navigate = navigate.clone();
+        move |target: &Target| {
+            let frame = target.token.to_frame(FrameColor::Blue);
+            context.mandelbrot.lock().unwrap().move_into_bounds(&frame.bounds);
+            if target.kind == FrameKind::Token {
+                navigate(
+                    &preserve_log_level(format!("/tokens/{}", target.token.token_id), query_map),
+                    Default::default(),
+                );
+            }
+        }
+    };
+
+    let burn_token = Action::new_local({
+        let context = context.clone();
+        move |&token_id: &u128| {
+            let context = context.clone();
+            async move {
+                if let Some(address) = context.state.address().get_untracked() {
+                    if context.contract.burn(address, token_id).await.is_some() {
+                        context.state.explorer().children().update(|children| {
+                            children.remove(&token_id);
+                        });
+                        context.state.inventory().tokens().update(|tokens| {
+                            tokens.remove(&token_id);
+                        });
+                    }
+                }
+            }
+        }
+    });
+
+    let delete_bid = Action::new_local({
+        let context = context.clone();
+        move |&bid_id: &u128| {
+            let context = context.clone();
+            async move {
+                if let Some(address) = context.state.address().get_untracked() {
+                    if context.contract.delete_bid(address, bid_id).await.is_some() {
+                        context.state.explorer().bids().update(|bids| {
+                            bids.remove(&bid_id);
+                        });
+                        context.state.inventory().bids().update(|bids| {
+                            bids.remove(&bid_id);
+                        });
+                    }
+                }
+            }
+        }
+    });
+
+    let set_minimum_bid = Action::new_local({
+        let context = context.clone();
+        move |&token_id: &u128| {
+            let context = context.clone();
+            async move {
+                if let Some(address) = context.state.address().get_untracked() {
+                    let minimum_price = bids_minimum_price.get_untracked();
+                    if context
+                        .contract
+                        .set_minimum_bid(address, token_id, minimum_price)
+                        .await
+                        .is_some()
+                    {
+                        context.state.explorer().children().update(|children| {
+                            if let Some(token) = children.get_mut(&token_id) {
+                                token.minimum_price = minimum_price;
+                            }
+                        });
+                    }
+                }
+            }
+        }
+    });
+
+    view! {
+        {move || hovered.get().map(|Target { kind, token, x, y }| view! {
+            <div
+                class="fixed z-40 pointer-events-none bg-gray-900/90 text-white text-xs font-mono rounded-md shadow-lg px-3 py-2 space-y-1"
+                style=format!("left: {}px; top: {}px;", x + 12, y + 12)
+            >
+                <div class="font-semibold">
+                    {format!("{} {}", if kind == FrameKind::Bid { "Bid" } else { "NFT" }, token.token_id)}
+                </div>
+                <div>
+                    {format!(
+                        "Owner: {}",
+                        address_book.with_untracked(|address_book| display_name(address_book, &token.owner)),
+                    )}
+                </div>
+                <div>{format!("Locked OM: {}", token.locked_tokens)}</div>
+                <div>{format!("Minimum bid: {}", token.minimum_price)}</div>
+                <div>{format!("Layer: {}", token.layer)}</div>
+            </div>
+        })}
+
+        {move || menu.get().map(|target| {
+            let zoom = zoom.clone();
+            let context = context.clone();
+            let token_id = target.token.token_id;
+            let is_bid = target.kind == FrameKind::Bid;
+            let owned = target.token.owned;
+            let selected = target.token.selected;
+            let bounds = format!(
+                "x_min={} x_max={} y_min={} y_max={}",
+                target.token.field.x_min, target.token.field.x_max, target.token.field.y_min, target.token.field.y_max,
+            );
+            view! {
+                <div
+                    class="fixed inset-0 z-40"
+                    on:click=move |_| close_menu()
+                    on:contextmenu=move |ev| {
+                        ev.prevent_default();
+                        close_menu();
+                    }
+                />
+                <div
+                    class="fixed z-50 min-w-[180px] flex flex-col bg-gray-900 text-white text-sm rounded-md shadow-lg py-1"
+                    style=format!("left: {}px; top: {}px;", target.x, target.y)
+                >
+                    <div class="px-3 py-1 text-xs text-gray-400 border-b border-gray-700">
+                        {format!("{} {}", if is_bid { "Bid" } else { "NFT" }, token_id)}
+                    </div>
+                    <button
+                        class="px-3 py-1 text-left hover:bg-gray-700"
+                        on:click={
+                            let zoom = zoom.clone();
+                            let target = target.clone();
+                            move |_| {
+                                zoom(&target);
+                                close_menu();
+                            }
+                        }
+                    >
+                        "Zoom"
+                    </button>
+                    <button
+                        class="px-3 py-1 text-left hover:bg-gray-700"
+                        on:click={
+                            let zoom = zoom.clone();
+                            let target = target.clone();
+                            let context = context.clone();
+                            move |_| {
+                                if !is_bid {
+                                    zoom(&target);
+                                }
+                                context.state.selected_tab().set(Tab::Auction);
+                                close_menu();
+                            }
+                        }
+                    >
+                        "Open in Auction"
+                    </button>
+                    {is_bid.then(|| {
+                        let context = context.clone();
+                        view! {
+                            <button
+                                class="px-3 py-1 text-left hover:bg-gray-700"
+                                on:click=move |_| {
+                                    context.select_bid(token_id, !selected);
+                                    close_menu();
+                                }
+                            >
+                                {if selected { "Deselect bid" } else { "Select bid" }}
+                            </button>
+                        }
+                    })}
+                    <button
+                        class="px-3 py-1 text-left hover:bg-gray-700"
+                        on:click=move |_| {
+                            copy_to_clipboard(&token_id.to_string());
+                            close_menu();
+                        }
+                    >
+                        "Copy id"
+                    </button>
+                    <button
+                        class="px-3 py-1 text-left hover:bg-gray-700"
+                        on:click=move |_| {
+                            copy_to_clipboard(&bounds);
+                            close_menu();
+                        }
+                    >
+                        "Copy bounds"
+                    </button>
+                    {(owned && is_bid).then(|| view! {
+                        <button
+                            class="px-3 py-1 text-left text-red-400 hover:bg-gray-700"
+                            on:click=move |_| {
+                                delete_bid.dispatch(token_id);
+                                close_menu();
+                            }
+                        >
+                            "Delete bid"
+                        </button>
+                    })}
+                    {(owned && !is_bid).then(|| view! {
+                        <button
+                            class="px-3 py-1 text-left hover:bg-gray-700"
+                            on:click=move |_| editing.update(|editing| *editing = !*editing)
+                        >
+                            "Edit"
+                        </button>
+                        <Show when=move || editing.get()>
+                            <div class="flex items-center gap-2 px-3 py-1">
+                                <input
+                                    type="number"
+                                    class="w-24 px-2 py-1 bg-gray-800 text-white rounded-md border border-gray-600 focus:outline-none focus:ring-2 focus:ring-accent1"
+                                    prop:value=move || bids_minimum_price.get()
+                                    on:input=move |ev| {
+                                        if let Ok(value) = event_target_value(&ev).parse::<f64>() {
+                                            bids_minimum_price.set(value);
+                                        }
+                                    }
+                                />
+                                <button
+                                    class="px-2 py-1 bg-blue-600 hover:bg-blue-500 rounded-md text-xs font-semibold transition"
+                                    on:click=move |_| {
+                                        set_minimum_bid.dispatch(token_id);
+                                        close_menu();
+                                    }
+                                >
+                                    "Save"
+                                </button>
+                            </div>
+                        </Show>
+                        <button
+                            class="px-3 py-1 text-left text-red-400 hover:bg-gray-700"
+                            on:click=move |_| {
+                                burn_token.dispatch(token_id);
+                                close_menu();
+                            }
+                        >
+                            "Burn"
+                        </button>
+                    })}
+                </div>
+            }
+        })}
+    }
+}
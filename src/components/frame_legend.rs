@@ -0,0 +1,71 @@
+use leptos::prelude::*;
+use reactive_stores::{Store, Subfield};
+use send_wrapper::SendWrapper;
+
+use crate::context::{Context, FrameFilters, FrameFiltersStoreFields, State, StateStoreFields};
+
+#[component]
+fn LegendRow(
+    filter: Subfield<Subfield<Store<State>, State, FrameFilters>, FrameFilters, bool>,
+    swatches: &'static [&'static str],
+    label: &'static str,
+) -> impl IntoView {
+    view! {
+        <label class="flex items-center gap-2 cursor-pointer">
+            <input
+                type="checkbox"
+                prop:checked=move || filter.get()
+                on:change=move |ev| filter.set(event_target_checked(&ev))
+                class="accent-accent1 w-4 h-4"
+            />
+            {swatches
+                .iter()
+                .map(|swatch| view! { <span class=format!("inline-block w-3 h-3 border-2 {}", swatch)/> })
+                .collect_view()}
+            <span>{label}</span>
+        </label>
+    }
+}
+
+#[component]
+pub fn FrameLegend() -> impl IntoView {
+    let context = use_context::<SendWrapper<Context>>().unwrap();
+    let filters = context.state.frame_filters();
+    let open = RwSignal::new(false);
+
+    view! {
+        <div
+            class="fixed left-2 z-30 -translate-y-full bg-gray-900/80 text-white text-xs rounded-md shadow-lg"
+            style="top: calc(var(--canvas-size) - 0.5rem)"
+        >
+            <button
+                on:click=move |_| open.update(|open| *open = !*open)
+                class="w-full px-3 py-1 text-left font-semibold hover:bg-gray-700 rounded-md"
+            >
+                {move || if open.get() { "Legend ▾" } else { "Legend ▸" }}
+            </button>
+            <Show when=move || open.get()>
+                <div class="flex flex-col gap-1 px-3 pb-2">
+                    <LegendRow
+                        filter=filters.ancestry()
+                        swatches=&["border-blue-600", "border-sky-300"]
+                        label="Current NFT and its ancestors, yours in light blue"
+                    />
+                    <LegendRow
+                        filter=filters.others_children()
+                        swatches=&["border-red-500"]
+                        label="NFTs minted inside the current one"
+                    />
+                    <LegendRow filter=filters.own_tokens() swatches=&["border-pink-400"] label="Your NFTs"/>
+                    <LegendRow filter=filters.others_bids() swatches=&["border-yellow-400"] label="Bids"/>
+                    <LegendRow filter=filters.own_bids() swatches=&["border-lime-300"] label="Your bids"/>
+                    <LegendRow
+                        filter=filters.selected_bids()
+                        swatches=&["border-green-500"]
+                        label="Bids selected for approval"
+                    />
+                </div>
+            </Show>
+        </div>
+    }
+}
@@ -0,0 +1,202 @@
+use leptos::{ev, prelude::*};
+use leptos_use::{UseEventListenerOptions, use_event_listener_with_options, use_window};
+use mandelbrot_explorer::{Bounds, ISample};
+use send_wrapper::SendWrapper;
+use wasm_bindgen::JsCast;
+
+use super::frame_control::is_bid_frame;
+use crate::{
+    context::{Context, ExplorerStoreFields, StateStoreFields},
+    coordinates::{contains, intersects, plane_point},
+};
+
+#[derive(Clone, Copy)]
+struct RubberBand {
+    start: (f64, f64),
+    end: (f64, f64),
+    deselect: bool,
+}
+
+/// Position of a pointer event relative to the canvas, as fractions of its width and height.
+pub(super) fn canvas_position(canvas: &web_sys::HtmlCanvasElement, x: f64, y: f64) -> Option<(f64, f64)> {
+    let rect = canvas.get_bounding_client_rect();
+    (rect.width() > 0.0 && rect.height() > 0.0).then(|| {
+        (
+            ((x - rect.left()) / rect.width()).clamp(0.0, 1.0),
+            ((y - rect.top()) / rect.height()).clamp(0.0, 1.0),
+        )
+    })
+}
+
+/// Ctrl+drag (Cmd on macOS) on the canvas selects every bid frame touched by the rectangle, adding Alt deselects
+/// them instead. Shift+click adds a single bid frame to the selection.
+#[component]
+pub fn FrameSelection() -> impl IntoView {
+    let context = use_context::<SendWrapper<Context>>().unwrap();
+    let canvas = use_context::<NodeRef<leptos::html::Canvas>>().unwrap();
+    let rubber_band = RwSignal::new(None::<RubberBand>);
+
+    let is_canvas_event = move |ev: &web_sys::PointerEvent| {
+        let target = ev
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::HtmlCanvasElement>().ok());
+        target.is_some() && target == canvas.get_untracked()
+    };
+
+    let select_bids_within = {
+        let context = context.clone();
+        move |rubber_band: RubberBand, canvas: &web_sys::HtmlCanvasElement| {
+            let (Some(start), Some(end)) = (
+                canvas_position(canvas, rubber_band.start.0, rubber_band.start.1),
+                canvas_position(canvas, rubber_band.end.0, rubber_band.end.1),
+            ) else {
+                return;
+            };
+            let bid_ids = {
+                let mandelbrot = context.mandelbrot.lock().unwrap();
+                let bounds = mandelbrot.engine.borrow().get_bounds();
+                let (x_min, y_max) = plane_point(&bounds, start.0.min(end.0), start.1.min(end.1));
+                let (x_max, y_min) = plane_point(&bounds, start.0.max(end.0), start.1.max(end.1));
+                let selection = Bounds {
+                    x_min,
+                    x_max,
+                    y_min,
+                    y_max,
+                };
+                mandelbrot
+                    .frames
+                    .iter()
+                    .filter(|frame| is_bid_frame(&frame.color) && intersects(&frame.bounds, &selection))
+                    .map(|frame| frame.id)
+                    .collect::<Vec<_>>()
+            };
+            context.select_bids(&bid_ids, !rubber_band.deselect);
+        }
+    };
+
+    let select_bid_at = {
+        let context = context.clone();
+        move |ev: &web_sys::PointerEvent, canvas: &web_sys::HtmlCanvasElement| {
+            let Some((x, y)) = canvas_position(canvas, ev.client_x() as f64, ev.client_y() as f64) else {
+                return;
+            };
+            let bid_id = {
+                let mandelbrot = context.mandelbrot.lock().unwrap();
+                let bounds = mandelbrot.engine.borrow().get_bounds();
+                let (x, y) = plane_point(&bounds, x, y);
+                mandelbrot
+                    .frames
+                    .iter()
+                    .find(|frame| is_bid_frame(&frame.color) && contains(&frame.bounds, &x, &y))
+                    .map(|frame| frame.id)
+            };
+            if let Some(bid_id) = bid_id {
+                context.select_bid(bid_id, true);
+            }
+        }
+    };
+
+    // Listeners are registered on the window in the capture phase, so that the explorer doesn't pan while
+    // selecting.
+    let options = UseEventListenerOptions::default().capture(true);
+    let _ = use_event_listener_with_options(
+        use_window(),
+        ev::pointerdown,
+        move |ev| {
+            if !is_canvas_event(&ev) {
+                return;
+            }
+            if ev.ctrl_key() || ev.meta_key() {
+                ev.stop_propagation();
+                ev.prevent_default();
+                let position = (ev.client_x() as f64, ev.client_y() as f64);
+                rubber_band.set(Some(RubberBand {
+                    start: position,
+                    end: position,
+                    deselect: ev.alt_key(),
+                }));
+            } else if ev.shift_key() {
+                ev.stop_propagation();
+                ev.prevent_default();
+                if let Some(canvas) = canvas.get_untracked() {
+                    select_bid_at(&ev, &canvas);
+                }
+            }
+        },
+        options.clone(),
+    );
+    let _ = use_event_listener_with_options(
+        use_window(),
+        ev::pointermove,
+        move |ev| {
+            if rubber_band.with_untracked(Option::is_some) {
+                ev.stop_propagation();
+                rubber_band.update(|rubber_band| {
+                    if let Some(rubber_band) = rubber_band {
+                        rubber_band.end = (ev.client_x() as f64, ev.client_y() as f64);
+                    }
+                });
+            }
+        },
+        options.clone(),
+    );
+    let _ = use_event_listener_with_options(
+        use_window(),
+        ev::pointerup,
+        move |ev| {
+            if let Some(band) = rubber_band.get_untracked() {
+                ev.stop_propagation();
+                rubber_band.set(None);
+                if let Some(canvas) = canvas.get_untracked() {
+                    select_bids_within(band, &canvas);
+                }
+            }
+        },
+        options,
+    );
+
+    let selected_count = Memo::new({
+        let context = context.clone();
+        move |_| {
+            context
+                .state
+                .explorer()
+                .bids()
+                .with(|bids| bids.values().filter(|bid| bid.selected).count())
+        }
+    });
+
+    view! {
+        {move || rubber_band.get().map(|RubberBand { start, end, deselect }| view! {
+            <div
+                class=if deselect {
+                    "fixed z-30 pointer-events-none border-2 border-dashed border-red-400 bg-red-400/10"
+                } else {
+                    "fixed z-30 pointer-events-none border-2 border-dashed border-green-400 bg-green-400/10"
+                }
+                style=format!(
+                    "left: {}px; top: {}px; width: {}px; height: {}px;",
+                    start.0.min(end.0),
+                    start.1.min(end.1),
+                    (end.0 - start.0).abs(),
+                    (end.1 - start.1).abs(),
+                )
+            />
+        })}
+
+        <Show when=move || { selected_count.get() > 0 }>
+            <div class="fixed left-2 top-2 z-30 flex items-center gap-3 bg-gray-900/80 text-white text-xs rounded-md shadow-lg px-3 py-1">
+                <span>{move || format!("{} bids selected", selected_count.get())}</span>
+                <button
+                    on:click={
+                        let context = context.clone();
+                        move |_| context.clear_selection()
+                    }
+                    class="px-2 py-1 bg-gray-700 hover:bg-gray-600 rounded-md font-semibold transition"
+                >
+                    "Clear selection"
+                </button>
+            </div>
+        </Show>
+    }
+}
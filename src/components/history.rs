@@ -0,0 +1,129 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use leptos::prelude::*;
+use mandelbrot_explorer::Focus;
+use url::Url;
+use wasm_bindgen::JsValue;
+
+const SETTLE_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Default)]
+struct State {
+    committed_url: Option<String>,
+    base_url: Option<String>,
+    settle_timeout: Option<TimeoutHandle>,
+    restoring: bool,
+    /// Entry restored without a focus, which the view moves into once the token has been loaded.
+    restored_url: Option<String>,
+}
+
+/// Keeps browser history in sync with the focus of the explorer. While the view is moving the current entry is
+/// only replaced, and a new entry is pushed once the view has been still for `SETTLE_DELAY`.
+#[derive(Clone)]
+pub struct FocusHistory(Rc<RefCell<State>>);
+
+fn current_url() -> Option<String> {
+    let location = web_sys::window()?.location();
+    Some(format!("{}{}", location.pathname().ok()?, location.search().ok()?))
+}
+
+/// Token of a `/tokens/:token_id` path, the root token for any other path.
+pub fn token_id(url: &str) -> u128 {
+    url.split(['?', '#'])
+        .next()
+        .and_then(|path| path.strip_prefix("/tokens/"))
+        .and_then(|token_id| token_id.trim_end_matches('/').parse().ok())
+        .unwrap_or(1)
+}
+
+impl FocusHistory {
+    /// The page the app was opened on counts as settled, so that the first interaction keeps it in history.
+    pub fn new() -> Self {
+        Self(Rc::new(RefCell::new(State {
+            committed_url: current_url(),
+            ..Default::default()
+        })))
+    }
+
+    pub fn update(&self, url: String) {
+        let mut state = self.0.borrow_mut();
+        if let Some(settle_timeout) = state.settle_timeout.take() {
+            settle_timeout.clear();
+        }
+        if !state.restoring {
+            if state.base_url.is_none() {
+                state.base_url = current_url();
+            }
+            if let Some(history) = web_sys::window().and_then(|window| window.history().ok()) {
+                let _ = history.replace_state_with_url(&JsValue::NULL, "", Some(&url));
+            }
+        }
+        state.settle_timeout = set_timeout_with_handle(
+            {
+                let this = self.clone();
+                move || this.settle(url)
+            },
+            SETTLE_DELAY,
+        )
+        .ok();
+    }
+
+    fn settle(&self, url: String) {
+        let mut state = self.0.borrow_mut();
+        state.settle_timeout = None;
+        if state.restoring {
+            state.restoring = false;
+            return;
+        }
+        // Only keep the entry the interaction started from if it was a settled view, entries pushed by the
+        // router are simply taken over. Moving into a restored entry without a focus only fills its focus in,
+        // pushing would drop the entries ahead of it.
+        let restored_url = state.restored_url.take();
+        if let Some(base_url) = state.base_url.take() {
+            if state.committed_url.as_ref() == Some(&base_url)
+                && base_url != url
+                && restored_url.as_ref() != Some(&base_url)
+            {
+                if let Some(history) = web_sys::window().and_then(|window| window.history().ok()) {
+                    let _ = history.replace_state_with_url(&JsValue::NULL, "", Some(&base_url));
+                    let _ = history.push_state_with_url(&JsValue::NULL, "", Some(&url));
+                }
+            }
+        }
+        state.committed_url = Some(url);
+    }
+
+    /// Called on `popstate`, returns the restored url and the focus stored in it. Focus changes caused by moving
+    /// into the restored focus, or into the token of an entry without one, don't create new entries.
+    pub fn restore(&self) -> Option<(String, Option<Focus>)> {
+        let mut state = self.0.borrow_mut();
+        if let Some(settle_timeout) = state.settle_timeout.take() {
+            settle_timeout.clear();
+        }
+        let url = current_url()?;
+        let focus = Url::parse(&web_sys::window()?.location().href().ok()?)
+            .ok()?
+            .query_pairs()
+            .find(|(key, _)| key == "focus")
+            .and_then(|(_, focus)| focus.parse().ok());
+        state.base_url = None;
+        state.committed_url = Some(url.clone());
+        state.restoring = focus.is_some();
+        if focus.is_none() {
+            // the move into the token comes whenever its metadata has loaded, its own settling clears this
+            state.restored_url = Some(url.clone());
+            return Some((url, focus));
+        }
+        state.restored_url = None;
+        state.settle_timeout = set_timeout_with_handle(
+            {
+                let this = self.clone();
+                let url = url.clone();
+                move || this.settle(url)
+            },
+            SETTLE_DELAY,
+        )
+        .ok();
+        Some((url, focus))
+    }
+}
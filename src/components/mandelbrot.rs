@@ -1,33 +1,49 @@
 use std::sync::{Arc, Mutex};
 
-use leptos::prelude::*;
+use leptos::{ev, prelude::*};
 use mandelbrot_explorer::ISample;
 use send_wrapper::SendWrapper;
 use web_sys::HtmlCanvasElement;
 
+/// Side of the square canvas: the height of the window beside the side panel in landscape, its width above the
+/// panel in portrait.
+pub fn canvas_size() -> f64 {
+    let window = web_sys::window().unwrap();
+    let width = window.inner_width().unwrap().as_f64().unwrap();
+    let height = window.inner_height().unwrap().as_f64().unwrap() + 1.0;
+    width.min(height)
+}
+
 #[component]
-pub fn Mandelbrot(interface: SendWrapper<Arc<Mutex<mandelbrot_explorer::Interface>>>) -> impl IntoView {
-    let canvas = NodeRef::new();
+pub fn Mandelbrot(
+    interface: SendWrapper<Arc<Mutex<mandelbrot_explorer::Interface>>>,
+    canvas: NodeRef<leptos::html::Canvas>,
+    size: RwSignal<f64>,
+    resolution_scale: Signal<Option<f64>>,
+) -> impl IntoView {
     let window = web_sys::window().unwrap();
-    let height = RwSignal::new(window.inner_height().unwrap().as_f64().unwrap() + 1.0);
-    let device_pixel_ratio = RwSignal::new(window.device_pixel_ratio());
+    let pixel_ratio = RwSignal::new(window.device_pixel_ratio());
 
-    let resize_callback = Arc::new({
-        let window = window.clone();
-        move || height.set(window.inner_height().unwrap().as_f64().unwrap() + 1.0)
+    let resize_callback = Arc::new(move || size.set(canvas_size()));
+    // in portrait the width of the window limits the canvas, so follow every resize
+    let _ = window_event_listener(ev::resize, move |_| {
+        let new_size = canvas_size();
+        if size.get_untracked() != new_size {
+            size.set(new_size);
+        }
     });
 
     Effect::new({
         let interface = interface.clone();
         move |_| {
-            let pixel_ratio = window.device_pixel_ratio();
-            device_pixel_ratio.set(pixel_ratio);
+            let ratio = resolution_scale.get().unwrap_or_else(|| window.device_pixel_ratio());
+            pixel_ratio.set(ratio);
             interface
                 .lock()
                 .unwrap()
                 .engine
                 .borrow_mut()
-                .resize((height.get() * pixel_ratio) as u32, (height.get() * pixel_ratio) as u32);
+                .resize((size.get() * ratio) as u32, (size.get() * ratio) as u32);
         }
     });
     canvas.on_load(|canvas: HtmlCanvasElement| {
@@ -36,12 +52,12 @@ pub fn Mandelbrot(interface: SendWrapper<Arc<Mutex<mandelbrot_explorer::Interfac
 
     view! {
         <canvas
-            class="outline-none"
+            class="outline-none touch-none shrink-0"
             node_ref=canvas
-            width=move || height.get() * device_pixel_ratio.get()
-            height=move || height.get() * device_pixel_ratio.get()
-            style:width=move || format!("{}px", height.get().max(1.0).to_string())
-            style:height=move || format!("{}px", height.get().max(1.0).to_string())
+            width=move || size.get() * pixel_ratio.get()
+            height=move || size.get() * pixel_ratio.get()
+            style:width=move || format!("{}px", size.get().max(1.0).to_string())
+            style:height=move || format!("{}px", size.get().max(1.0).to_string())
         />
     }
 }
@@ -0,0 +1,237 @@
+use leptos::{prelude::*, task::spawn_local};
+use mandelbrot_explorer::{BigFloat, Bounds, FrameColor, ISample};
+use send_wrapper::SendWrapper;
+use wasm_bindgen::JsCast;
+
+use crate::{
+    color::{Gradient, WaveGradient},
+    context::{Context, ExplorerStoreFields, RenderSettingsStoreFields, StateStoreFields},
+    coordinates::{plane_point, to_f64, whole_set, width},
+    snapshot::{Coloring, OffscreenRenderer},
+};
+
+// side of the minimap in canvas pixels
+const SIZE: u32 = 160;
+// smallest side of a drawn box, so that deep views stay visible
+const MIN_BOX_SIZE: f64 = 3.0;
+
+/// Position of `bounds` inside `area`, in minimap pixels, as (x, y, width, height).
+fn project(bounds: &Bounds, area: &Bounds) -> (f64, f64, f64, f64) {
+    let scale = SIZE as f64 / to_f64(&width(area));
+    let x = to_f64(&(&bounds.x_min - area.x_min.clone())) * scale;
+    let y = to_f64(&(&area.y_max - bounds.y_max.clone())) * scale;
+    let bounds_width = to_f64(&width(bounds)) * scale;
+    let bounds_height = to_f64(&(&bounds.y_max - bounds.y_min.clone())) * scale;
+    let (box_width, box_height) = (bounds_width.max(MIN_BOX_SIZE), bounds_height.max(MIN_BOX_SIZE));
+    // keep small boxes centered on what they stand for
+    (
+        x - (box_width - bounds_width) / 2.0,
+        y - (box_height - bounds_height) / 2.0,
+        box_width,
+        box_height,
+    )
+}
+
+#[component]
+pub fn Minimap() -> impl IntoView {
+    let context = use_context::<SendWrapper<Context>>().unwrap();
+    let open = RwSignal::new(false);
+    // token whose field the minimap covers, the whole set when `None`
+    let level = RwSignal::new(None::<u128>);
+    let dragging = RwSignal::new(false);
+    let canvas_ref = NodeRef::<leptos::html::Canvas>::new();
+    let nav_history = context.state.explorer().nav_history();
+
+    let area = Signal::derive(move || {
+        level
+            .get()
+            .and_then(|token_id| {
+                nav_history.with(|nav_history| {
+                    nav_history
+                        .iter()
+                        .find(|token| token.token_id == token_id)
+                        .map(|token| token.to_frame(FrameColor::Blue).bounds)
+                })
+            })
+            .unwrap_or_else(whole_set)
+    });
+
+    // the set itself only changes with the area, so an offscreen engine renders it once into `image`, which is
+    // redrawn under the boxes
+    let image = StoredValue::new_local(None::<web_sys::HtmlCanvasElement>);
+    let rendered = RwSignal::new(false);
+    // only the latest rendering is kept, when the area changes before an earlier one has finished
+    let renderings = StoredValue::new(0u32);
+    Effect::new({
+        let context = context.clone();
+        move || {
+            let area = area.get();
+            if !open.get() {
+                return;
+            }
+            let coloring = Coloring {
+                gradient: Gradient::Wave(WaveGradient::default()),
+                max_iterations: 500,
+                offset: 0.0,
+                length: 50.0,
+            };
+            let engine = context.state.render_settings().engine().get_untracked();
+            let Some(renderer) = OffscreenRenderer::acquire(engine, SIZE, &coloring) else {
+                return;
+            };
+            rendered.set(false);
+            renderings.update_value(|renderings| *renderings += 1);
+            let rendering = renderings.get_value();
+            spawn_local(async move {
+                let set = renderer.render_bounds(&area).await;
+                if renderings.get_value() != rendering {
+                    return;
+                }
+                let Some(copy) = image.get_value().or_else(|| {
+                    let canvas = document()
+                        .create_element("canvas")
+                        .ok()?
+                        .dyn_into::<web_sys::HtmlCanvasElement>()
+                        .ok()?;
+                    canvas.set_width(SIZE);
+                    canvas.set_height(SIZE);
+                    Some(canvas)
+                }) else {
+                    return;
+                };
+                let ctx = copy
+                    .get_context("2d")
+                    .unwrap()
+                    .unwrap()
+                    .dyn_into::<web_sys::CanvasRenderingContext2d>()
+                    .unwrap();
+                let _ = ctx.draw_image_with_html_canvas_element(set, 0.0, 0.0);
+                image.set_value(Some(copy));
+                rendered.set(true);
+            });
+        }
+    });
+
+    Effect::new({
+        let context = context.clone();
+        move || {
+            context.state.focus().track();
+            if !open.get() {
+                return;
+            }
+            let Some(canvas) = canvas_ref.get() else {
+                return;
+            };
+            let ctx = canvas
+                .get_context("2d")
+                .unwrap()
+                .unwrap()
+                .dyn_into::<web_sys::CanvasRenderingContext2d>()
+                .unwrap();
+            match image.get_value().filter(|_| rendered.get()) {
+                Some(image) => {
+                    let _ = ctx.draw_image_with_html_canvas_element(&image, 0.0, 0.0);
+                }
+                None => {
+                    ctx.set_fill_style_str("#111827");
+                    ctx.fill_rect(0.0, 0.0, SIZE as f64, SIZE as f64);
+                }
+            }
+
+            let area = area.get();
+            ctx.set_line_width(1.0);
+            nav_history.with(|nav_history| {
+                for (index, token) in nav_history.iter().enumerate() {
+                    let is_current = index + 1 == nav_history.len();
+                    ctx.set_stroke_style_str(if is_current { "#7dd3fc" } else { "#2563eb" });
+                    let (x, y, width, height) = project(&token.to_frame(FrameColor::Blue).bounds, &area);
+                    ctx.stroke_rect(x, y, width, height);
+                }
+            });
+
+            let bounds = context.mandelbrot.lock().unwrap().engine.borrow().get_bounds();
+            let (x, y, width, height) = project(&bounds, &area);
+            ctx.set_line_width(2.0);
+            ctx.set_stroke_style_str("#ffffff");
+            ctx.stroke_rect(x, y, width, height);
+        }
+    });
+
+    // center the main view on the point under the pointer, keeping its size
+    let move_to = {
+        let context = context.clone();
+        move |ev: &web_sys::PointerEvent| {
+            let Some(canvas) = canvas_ref.get_untracked() else {
+                return;
+            };
+            let rect = canvas.get_bounding_client_rect();
+            if rect.width() <= 0.0 || rect.height() <= 0.0 {
+                return;
+            }
+            let (x, y) = plane_point(
+                &area.get_untracked(),
+                ((ev.client_x() as f64 - rect.left()) / rect.width()).clamp(0.0, 1.0),
+                ((ev.client_y() as f64 - rect.top()) / rect.height()).clamp(0.0, 1.0),
+            );
+            let bounds = context.mandelbrot.lock().unwrap().engine.borrow().get_bounds();
+            let half_width = width(&bounds) * BigFloat::from(0.5);
+            let half_height = (&bounds.y_max - bounds.y_min.clone()) * BigFloat::from(0.5);
+            let target = Bounds {
+                x_min: &x - half_width.clone(),
+                x_max: &x + &half_width,
+                y_min: &y - half_height.clone(),
+                y_max: &y + &half_height,
+            };
+            context.mandelbrot.lock().unwrap().move_into_bounds(&target);
+        }
+    };
+
+    view! {
+        <div
+            class="fixed top-2 z-30 bg-gray-900/80 text-white text-xs rounded-md shadow-lg"
+            style="left: calc(var(--canvas-size) - 11rem)"
+        >
+            <button
+                on:click=move |_| open.update(|open| *open = !*open)
+                class="w-full px-3 py-1 text-left font-semibold hover:bg-gray-700 rounded-md"
+            >
+                {move || if open.get() { "Minimap ▾" } else { "Minimap ▸" }}
+            </button>
+            <Show when=move || open.get()>
+                <div class="flex flex-col gap-1 px-2 pb-2">
+                    <select
+                        on:change=move |ev| level.set(event_target_value(&ev).parse().ok())
+                        class="px-1 rounded bg-gray-800 border border-gray-600"
+                    >
+                        <option value="" selected=move || level.get().is_none()>"Whole set"</option>
+                        {move || nav_history.get().into_iter().map(|token| view! {
+                            <option value=token.token_id.to_string() selected=move || level.get() == Some(token.token_id)>
+                                {format!("NFT #{}", token.token_id)}
+                            </option>
+                        }).collect_view()}
+                    </select>
+                    <canvas
+                        node_ref=canvas_ref
+                        width=SIZE
+                        height=SIZE
+                        class="rounded cursor-crosshair touch-none"
+                        style=format!("width: {}px; height: {}px", SIZE, SIZE)
+                        on:pointerdown={
+                            let move_to = move_to.clone();
+                            move |ev| {
+                                dragging.set(true);
+                                move_to(&ev);
+                            }
+                        }
+                        on:pointermove={
+                            let move_to = move_to.clone();
+                            move |ev| if dragging.get_untracked() { move_to(&ev) }
+                        }
+                        on:pointerup=move |_| dragging.set(false)
+                        on:pointerleave=move |_| dragging.set(false)
+                    />
+                </div>
+            </Show>
+        </div>
+    }
+}
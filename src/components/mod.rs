@@ -1,110 +1,227 @@
 mod account;
 mod context;
+mod coordinate_hud;
 mod error_handler;
 mod frame_control;
+mod frame_inspector;
+mod frame_legend;
+mod frame_selection;
+mod history;
 mod mandelbrot;
+mod minimap;
+mod notification_centre;
 mod primitive;
+mod shortcuts;
 mod tabs;
+mod touch_control;
 
 use std::{
     cell::RefCell,
-    // f32::consts::PI,
     rc::Rc,
     sync::{Arc, Mutex},
 };
 
-use leptos::prelude::*;
+use leptos::{ev, prelude::*};
 use leptos_ethereum_provider::{ConnectButton, EthereumContextProvider};
 use leptos_router::{
     components::{Route, Routes},
     hooks::use_query_map,
     path,
 };
+use mandelbrot_explorer::{FrameColor, ISample};
 use reactive_stores::Store;
 
-use crate::{context::StateStoreFields, util::preserve_log_level};
+use crate::{
+    color::{Gradient, WaveGradient},
+    context::{Engine, ExplorerStoreFields, RenderSettingsStoreFields, State, StateStoreFields},
+    evm::types::Metadata,
+    share,
+    util::{load_item, preserve_log_level},
+};
+use coordinate_hud::CoordinateHud;
 use frame_control::FrameControl;
+use frame_inspector::FrameInspector;
+use frame_legend::FrameLegend;
+use frame_selection::FrameSelection;
+use history::FocusHistory;
 
 use {
     account::{Account, AccountButton},
     context::ContextProvider,
     mandelbrot::Mandelbrot,
+    minimap::Minimap,
+    notification_centre::NotificationCentre,
+    shortcuts::Shortcuts,
     tabs::Tabs,
+    touch_control::TouchControl,
 };
 
 #[component]
 pub fn App() -> impl IntoView {
     let query_map = use_query_map();
-    let window = web_sys::window().unwrap();
-    let height = window.inner_height().unwrap().as_f64().unwrap() + 1.0;
-    let state = Store::default();
+    let canvas_size = RwSignal::new(mandelbrot::canvas_size());
+    let shared = query_map
+        .get_untracked()
+        .get("share")
+        .map(|shared| share::decode(&shared));
+    provide_context(shared.clone());
+    let state = Store::new(State {
+        frame_filters: load_item("frame_filters").unwrap_or_default(),
+        address_book: load_item("address_book").unwrap_or_default(),
+        watchlist: load_item("watchlist").unwrap_or_default(),
+        notifications: load_item("notifications").unwrap_or_default(),
+        browser_notifications: load_item("browser_notifications").unwrap_or_default(),
+        render_settings: load_item("render_settings").unwrap_or_default(),
+        selected_tab: shared
+            .and_then(|shared| shared.ok())
+            .and_then(|shared| shared.tab)
+            .unwrap_or_default(),
+        ..Default::default()
+    });
+    let canvas = NodeRef::<leptos::html::Canvas>::new();
+    provide_context(canvas);
 
+    let focus_history = FocusHistory::new();
     let on_focus_change = {
+        let focus_history = focus_history.clone();
         move |focus| {
+            let focus = format!("{}", focus);
             let url = if let Some(token_id) = state.current_token_id().get_untracked() {
                 preserve_log_level(format!("/tokens/{}?focus={}", token_id, focus), query_map)
             } else {
                 preserve_log_level(format!("?focus={}", focus), query_map)
             };
-            if let Ok(history) = window.history() {
-                let _ = history.push_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&url));
-            }
+            focus_history.update(url);
+            state.focus().set(focus);
         }
     };
 
+    let create_engine = move |engine: Engine, width: u32, height: u32| match engine {
+        Engine::Perturbation => mandelbrot_explorer::Perturbation::new(width, height, on_focus_change.clone()).into(),
+        Engine::Optimised => mandelbrot_explorer::Optimised::new(width, height, on_focus_change.clone()).into(),
+    };
+
     let interface = LocalStorage::wrap(Arc::new(Mutex::new(mandelbrot_explorer::Interface::new(
-        Rc::new(RefCell::new(
-            mandelbrot_explorer::Perturbation::new(height as u32, height as u32, on_focus_change).into(),
-            // mandelbrot_explorer::Optimised::new(height as u32, height as u32, on_focus_change).into(),
-        )),
+        Rc::new(RefCell::new(create_engine(
+            state.render_settings().engine().get_untracked(),
+            canvas_size.get_untracked() as u32,
+            canvas_size.get_untracked() as u32,
+        ))),
         mandelbrot_explorer::Palette {
-            gradient: mandelbrot_explorer::Gradient::Wave(mandelbrot_explorer::WaveGradient {
-                // neon
-                red: mandelbrot_explorer::Wave::new(0.5, 0.7, 12.0, 0.0),
-                green: mandelbrot_explorer::Wave::new(0.5, 0.7, 10.0, 1.5),
-                blue: mandelbrot_explorer::Wave::new(0.5, 0.7, 8.0, 3.0),
-                // red velvet
-                // red: mandelbrot_explorer::Wave::new(0.337, 0.662, 6.28, 0.0),
-                // green: mandelbrot_explorer::Wave::new(0.245, 0.586, 6.28, 0.0),
-                // blue: mandelbrot_explorer::Wave::new(0.334, -0.343, 6.28, 0.0),
-
-                // evil rainbow
-                // red: mandelbrot_explorer::Wave::new(0.4, 0.6, 7.2, 1.2),
-                // green: mandelbrot_explorer::Wave::new(0.4, 0.6, 5.9, -1.6),
-                // blue: mandelbrot_explorer::Wave::new(0.4, 0.6, 3.8, 2.1),
-
-                // winter sunrise
-                // red: mandelbrot_explorer::Wave::new(1.0, 0.5, 6.28, 0.9),
-                // green: mandelbrot_explorer::Wave::new(1.0, 0.5, 5.88, -PI),
-                // blue: mandelbrot_explorer::Wave::new(1.0, 0.5, PI, -3.64)
-            }),
+            gradient: Gradient::from(WaveGradient::default()).into(),
             max_iterations: 1600,
             offset: 0.0,
             length: 360.0,
         },
     ))));
 
+    // swap the engine in place when another one is chosen, keeping the view
+    Effect::new({
+        let interface = interface.clone();
+        move |previous: Option<Engine>| {
+            let engine = state.render_settings().engine().get();
+            if previous.is_some_and(|previous| previous != engine) {
+                let size = canvas_size.get_untracked() as u32;
+                let (width, height) = canvas
+                    .get_untracked()
+                    .map_or((size, size), |canvas| (canvas.width(), canvas.height()));
+                let focus = interface.lock().unwrap().engine.borrow().focus();
+                *interface.lock().unwrap().engine.borrow_mut() = create_engine(engine, width, height);
+                interface.lock().unwrap().move_into_focus(focus);
+            }
+            engine
+        }
+    });
+
+    // restore focus when navigating through browser history, the router takes care of the token route; entries
+    // without a focus show the whole token once its metadata has been loaded
+    let pending_token_view = StoredValue::new(None::<u128>);
+    let move_into_token = {
+        let interface = interface.clone();
+        move |nav_history: &Vec<Metadata>| {
+            let pending = pending_token_view.get_value();
+            if let Some(token) = nav_history.last().filter(|token| pending == Some(token.token_id)) {
+                pending_token_view.set_value(None);
+                interface
+                    .lock()
+                    .unwrap()
+                    .move_into_bounds(&token.to_frame(FrameColor::Blue).bounds);
+            }
+        }
+    };
+    let _ = window_event_listener(ev::popstate, {
+        let interface = interface.clone();
+        let move_into_token = move_into_token.clone();
+        move |_| {
+            if let Some((url, focus)) = focus_history.restore() {
+                if let Some(focus) = focus {
+                    pending_token_view.set_value(None);
+                    interface.lock().unwrap().move_into_focus(focus);
+                } else {
+                    pending_token_view.set_value(Some(history::token_id(&url)));
+                    state
+                        .explorer()
+                        .nav_history()
+                        .with_untracked(|nav_history| move_into_token(nav_history));
+                }
+            }
+        }
+    });
+    Effect::new(move || {
+        state
+            .explorer()
+            .nav_history()
+            .with(|nav_history| move_into_token(nav_history))
+    });
+
     let account_open = RwSignal::new(false);
     let token_balance = RwSignal::new(0.0);
+    let panel_open = RwSignal::new(true);
 
+    // overlays are placed against the canvas through the --canvas-size variable
     view! {
-        <div class="min-h-screen flex flex-col">
-            <div class="flex flex-row items-stretch">
-                <Mandelbrot interface=interface.clone()/>
+        <div class="h-[100dvh] flex flex-col" style=move || format!("--canvas-size: {}px", canvas_size.get())>
+            <div class="flex-1 min-h-0 flex flex-col landscape:flex-row items-stretch">
+                <Mandelbrot
+                    interface=interface.clone()
+                    canvas
+                    size=canvas_size
+                    resolution_scale=Signal::derive(move || state.render_settings().resolution_scale().get())
+                />
                 <EthereumContextProvider>
                     <ContextProvider mandelbrot=interface.clone() state>
                         <FrameControl/>
-                        <div class="relative w-full overflow-auto">
+                        <CoordinateHud/>
+                        <Minimap/>
+                        <Shortcuts/>
+                        <TouchControl/>
+                        <FrameInspector/>
+                        <FrameLegend/>
+                        <FrameSelection/>
+                        <button
+                            on:click=move |_| panel_open.update(|panel_open| *panel_open = !*panel_open)
+                            class="fixed z-30 w-8 h-8 bg-gray-900/80 hover:bg-gray-700 text-white rounded-md shadow-lg"
+                            style="left: calc(var(--canvas-size) - 2.5rem); top: calc(var(--canvas-size) - 2.5rem)"
+                            title=move || if panel_open.get() { "Hide panel" } else { "Show panel" }
+                        >
+                            "☰"
+                        </button>
+                        <div class=move || {
+                            if panel_open.get() { "relative w-full flex-1 min-h-0 overflow-auto" } else { "hidden" }
+                        }>
                             <header class="h-[8vh] z-10 bg-brand text-white flex items-center justify-between px-4">
                                 <h3 class="text-lg font-bold">"Mandelbrot NFT"</h3>
-                                <ConnectButton connected_html=move || view! {
-                                    <AccountButton
-                                        balance=token_balance.read_only()
-                                        on_click=move || account_open.update(|account_open| {
-                                            *account_open = !*account_open;
-                                        })
-                                    />
-                                }/>
+                                <div class="flex items-center gap-4">
+                                    <NotificationCentre/>
+                                    <ConnectButton connected_html=move || view! {
+                                        <AccountButton
+                                            balance=token_balance.read_only()
+                                            on_click=move || account_open.update(|account_open| {
+                                                *account_open = !*account_open;
+                                            })
+                                        />
+                                    }/>
+                                </div>
                             </header>
                             <Routes fallback=|| "Not found.">
                                 <Route path=path!("/tokens/:token_id") view=move || view! { <Tabs/> }/>
@@ -0,0 +1,207 @@
+use std::{collections::HashMap, time::Duration};
+
+use leptos::{prelude::*, task::spawn_local};
+use leptos_router::hooks::{use_navigate, use_query_map};
+use send_wrapper::SendWrapper;
+use web_sys::{NotificationOptions, NotificationPermission};
+
+use crate::{
+    components::primitive::AddressTag,
+    context::{Context, SalesStoreFields, StateStoreFields, Tab},
+    evm::types::Metadata,
+    notifications::{Notification, NotificationKind, diff_bids, prepend, resolve_closed},
+    util::{format_timestamp, preserve_log_level},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+fn document_hidden() -> bool {
+    web_sys::window()
+        .and_then(|window| window.document())
+        .is_some_and(|document| document.hidden())
+}
+
+fn raise_browser_notification(notification: &Notification) {
+    if web_sys::Notification::permission() == NotificationPermission::Granted {
+        let options = NotificationOptions::new();
+        options.set_body(&notification.body());
+        let _ = web_sys::Notification::new_with_options(&notification.title(), &options);
+    }
+}
+
+#[component]
+pub fn NotificationCentre() -> impl IntoView {
+    let context = use_context::<SendWrapper<Context>>().unwrap();
+    let navigate = use_navigate();
+    let query_map = use_query_map();
+    let notifications = context.state.notifications();
+    let browser_notifications = context.state.browser_notifications();
+    let open = RwSignal::new(false);
+
+    let unread = Memo::new(move |_| {
+        notifications.with(|notifications| notifications.iter().filter(|notification| !notification.read).count())
+    });
+
+    // compare successive snapshots of bids on owned tokens
+    Effect::new({
+        let context = context.clone();
+        move |previous: Option<HashMap<u128, HashMap<u128, Metadata>>>| {
+            let current = context.state.sales().bids().get();
+            if let Some(previous) = previous {
+                let mut new = diff_bids(&previous, &current, js_sys::Date::now());
+                if !new.is_empty() {
+                    let context = context.clone();
+                    spawn_local(async move {
+                        // closed bids stay neutral when the children can't be loaded
+                        let mut children = HashMap::new();
+                        for notification in &mut new {
+                            if notification.kind != NotificationKind::BidClosed {
+                                continue;
+                            }
+                            if !children.contains_key(&notification.token_id) {
+                                let loaded = context.contract.get_children_metadata(notification.token_id).await.ok();
+                                children.insert(notification.token_id, loaded);
+                            }
+                            if let Some(Some(children)) = children.get(&notification.token_id) {
+                                resolve_closed(notification, children);
+                            }
+                        }
+                        if browser_notifications.get_untracked() && document_hidden() {
+                            new.iter().for_each(raise_browser_notification);
+                        }
+                        notifications.update(|notifications| prepend(notifications, new));
+                    });
+                }
+            }
+            current
+        }
+    });
+
+    // poll bids on owned tokens, so that new ones show up without a manual refresh
+    if let Ok(interval) = set_interval_with_handle(
+        {
+            let context = context.clone();
+            move || {
+                if context.state.address().get_untracked().is_some() {
+                    let context = context.clone();
+                    spawn_local(async move {
+                        context.reload_sales().await;
+                    });
+                }
+            }
+        },
+        POLL_INTERVAL,
+    ) {
+        on_cleanup(move || interval.clear());
+    }
+
+    let toggle_browser_notifications = move |ev| {
+        let enabled = event_target_checked(&ev);
+        browser_notifications.set(enabled);
+        if enabled && web_sys::Notification::permission() == NotificationPermission::Default {
+            let _ = web_sys::Notification::request_permission();
+        }
+    };
+
+    let open_notification = move |index: usize, token_id: u128| {
+        notifications.update(|notifications| {
+            if let Some(notification) = notifications.get_mut(index) {
+                notification.read = true;
+            }
+        });
+        navigate(
+            &preserve_log_level(format!("/tokens/{}", token_id), query_map),
+            Default::default(),
+        );
+        context.state.selected_tab().set(Tab::Sales);
+        open.set(false);
+    };
+
+    view! {
+        <div class="relative">
+            <button
+                on:click=move |_| open.update(|open| *open = !*open)
+                class="relative px-2 py-1 rounded-md hover:bg-gray-800 transition"
+                title="Notifications"
+            >
+                "🔔"
+                <Show when=move || { unread.get() > 0 }>
+                    <span class="absolute -top-1 -right-1 px-1.5 rounded-full bg-red-600 text-white text-xs">
+                        {move || unread.get()}
+                    </span>
+                </Show>
+            </button>
+
+            <Show when=move || open.get()>
+                <div class="absolute right-0 mt-2 z-50 w-[360px] max-h-[70vh] overflow-y-auto bg-gray-900 text-white rounded-md shadow-lg p-4 space-y-3">
+                    <div class="flex items-center justify-between">
+                        <span class="font-semibold">"Notifications"</span>
+                        <div class="flex gap-2">
+                            <button
+                                on:click=move |_| notifications.update(|notifications| {
+                                    notifications.iter_mut().for_each(|notification| notification.read = true)
+                                })
+                                class="px-2 py-1 bg-gray-700 hover:bg-gray-600 rounded-md text-xs font-semibold transition"
+                            >
+                                "Mark all read"
+                            </button>
+                            <button
+                                on:click=move |_| notifications.set(Vec::new())
+                                class="px-2 py-1 bg-gray-700 hover:bg-gray-600 rounded-md text-xs font-semibold transition"
+                            >
+                                "Clear"
+                            </button>
+                        </div>
+                    </div>
+
+                    <label class="flex items-center gap-2 text-xs text-gray-300">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || browser_notifications.get()
+                            on:change=toggle_browser_notifications
+                            class="accent-accent1 w-4 h-4"
+                        />
+                        "Browser notifications while the tab is in the background"
+                    </label>
+
+                    <Show
+                        when=move || notifications.with(|notifications| !notifications.is_empty())
+                        fallback=|| view! { <p class="text-sm text-gray-400">"No notifications"</p> }
+                    >
+                        <div class="space-y-2">
+                            {
+                                let open_notification = open_notification.clone();
+                                move || notifications.get().into_iter().enumerate().map(|(index, notification)| {
+                                    let open_notification = open_notification.clone();
+                                    let token_id = notification.token_id;
+                                    view! {
+                                        <div
+                                            on:click=move |_| open_notification(index, token_id)
+                                            class=if notification.read {
+                                                "px-3 py-2 rounded-md bg-gray-800 hover:bg-gray-700 cursor-pointer text-gray-400"
+                                            } else {
+                                                "px-3 py-2 rounded-md bg-gray-800 hover:bg-gray-700 cursor-pointer border-l-4 border-blue-500"
+                                            }
+                                        >
+                                            <div class="text-sm font-semibold">{notification.title()}</div>
+                                            <div class="flex items-center gap-2 text-xs">
+                                                {notification.body()}
+                                                <AddressTag address=notification.owner/>
+                                            </div>
+                                            <div class="text-xs text-gray-500">
+                                                {format_timestamp(notification.timestamp)}
+                                            </div>
+                                            {(notification.kind == NotificationKind::BidPlaced).then(|| view! {
+                                                <div class="text-xs text-accent2">"Review it in the Sales tab"</div>
+                                            })}
+                                        </div>
+                                    }
+                                }).collect_view()
+                            }
+                        </div>
+                    </Show>
+                </div>
+            </Show>
+        </div>
+    }
+}
@@ -0,0 +1,141 @@
+use leptos::prelude::*;
+use send_wrapper::SendWrapper;
+use web3::types::Address;
+
+use crate::{
+    address_book::{Contact, display_name, explorer_link, to_checksum},
+    context::{Context, StateStoreFields},
+};
+
+#[component]
+pub fn ContactEditor(address: Address, contact: RwSignal<Option<Contact>>) -> impl IntoView {
+    let context = use_context::<SendWrapper<Context>>().unwrap();
+    let address_book = context.state.address_book();
+
+    let save = move |_| {
+        if let Some(contact) = contact.get_untracked() {
+            address_book.update(|address_book| {
+                if contact.label.is_empty() && contact.notes.is_empty() {
+                    address_book.remove(&address);
+                } else {
+                    address_book.insert(address, contact);
+                }
+            });
+        }
+        contact.set(None);
+    };
+
+    view! {
+        <Show when=move || contact.with(Option::is_some)>
+            <div class="fixed inset-0 z-50 flex items-center justify-center bg-black bg-opacity-50">
+                <div class="bg-gray-900 text-white p-6 rounded-lg shadow-lg w-full max-w-md space-y-4">
+                    <div class="text-xl font-bold">"Address book"</div>
+                    <div class="text-xs font-mono text-gray-400 break-all">{to_checksum(&address)}</div>
+
+                    <div class="flex items-center gap-2">
+                        <input
+                            type="text"
+                            placeholder="Label"
+                            class="flex-1 bg-gray-800 text-white p-2 rounded-md focus:outline-none focus:ring-2 focus:ring-accent1"
+                            prop:value=move || contact.get().map(|contact| contact.label).unwrap_or_default()
+                            on:input=move |ev| contact.update(|contact| {
+                                if let Some(contact) = contact {
+                                    contact.label = event_target_value(&ev);
+                                }
+                            })
+                        />
+                        <input
+                            type="color"
+                            class="w-8 h-8 rounded border shadow"
+                            prop:value=move || contact.get().map(|contact| contact.color).unwrap_or_default()
+                            on:input=move |ev| contact.update(|contact| {
+                                if let Some(contact) = contact {
+                                    contact.color = event_target_value(&ev);
+                                }
+                            })
+                        />
+                    </div>
+                    <textarea
+                        placeholder="Notes"
+                        class="w-full h-24 bg-gray-800 text-white p-2 rounded-md focus:outline-none focus:ring-2 focus:ring-accent1"
+                        prop:value=move || contact.get().map(|contact| contact.notes).unwrap_or_default()
+                        on:input=move |ev| contact.update(|contact| {
+                            if let Some(contact) = contact {
+                                contact.notes = event_target_value(&ev);
+                            }
+                        })
+                    />
+
+                    <div class="flex justify-end gap-4 pt-2 border-t border-gray-700">
+                        <button
+                            on:click=save
+                            class="px-4 py-2 bg-blue-600 hover:bg-blue-500 rounded-md text-white font-semibold transition"
+                        >
+                            "Save"
+                        </button>
+                        <button
+                            on:click=move |_| contact.set(None)
+                            class="px-4 py-2 bg-gray-600 hover:bg-gray-500 rounded-md text-white font-semibold transition"
+                        >
+                            "Cancel"
+                        </button>
+                    </div>
+                </div>
+            </div>
+        </Show>
+    }
+}
+
+/// An address labelled from the address book, with buttons to copy it, open it in the block explorer and edit its
+/// address book entry.
+#[component]
+pub fn AddressTag(address: Address) -> impl IntoView {
+    let context = use_context::<SendWrapper<Context>>().unwrap();
+    let address_book = context.state.address_book();
+    let contact = Memo::new(move |_| address_book.with(|address_book| address_book.get(&address).cloned()));
+    let edited_contact = RwSignal::new(None);
+    let checksum = to_checksum(&address);
+
+    view! {
+        <span
+            class="inline-flex items-center gap-1 font-mono"
+            title={
+                let checksum = checksum.clone();
+                move || match contact.get() {
+                    Some(contact) if !contact.notes.is_empty() => format!("{}\n{}", checksum, contact.notes),
+                    _ => checksum.clone(),
+                }
+            }
+        >
+            {move || contact.get().filter(|contact| !contact.color.is_empty()).map(|contact| view! {
+                <span
+                    class="inline-block w-2 h-2 rounded-full"
+                    style=format!("background-color: {};", contact.color)
+                />
+            })}
+            <span>{move || address_book.with(|address_book| display_name(address_book, &address))}</span>
+            <button
+                title="Copy address"
+                class="text-gray-400 hover:text-white"
+                on:click=move |_| {
+                    let _ = web_sys::window().unwrap().navigator().clipboard().write_text(&checksum);
+                }
+            >
+                "⧉"
+            </button>
+            {explorer_link(&address).map(|link| view! {
+                <a href=link target="_blank" title="View in block explorer" class="text-gray-400 hover:text-white">
+                    "↗"
+                </a>
+            })}
+            <button
+                title="Edit address book entry"
+                class="text-gray-400 hover:text-white"
+                on:click=move |_| edited_contact.set(Some(contact.get_untracked().unwrap_or_default()))
+            >
+                "✎"
+            </button>
+        </span>
+        <ContactEditor address contact=edited_contact/>
+    }
+}
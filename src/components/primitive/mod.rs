@@ -1,3 +1,5 @@
+mod address;
 mod slider;
 
+pub use address::{AddressTag, ContactEditor};
 pub use slider::Slider;
@@ -4,6 +4,7 @@ use leptos::tachys::html::property::IntoProperty;
 
 #[component]
 pub fn Slider(
+    #[prop(into, optional)] min: Signal<f64>,
     #[prop(into)] max: Signal<f64>,
     value: impl IntoProperty + Get<Value = f64> + Set<Value = f64> + Copy + Send + 'static,
     class: &'static str,
@@ -18,7 +19,7 @@ pub fn Slider(
         <div>
             <input
                 type="range"
-                min="0.0"
+                min=move || min.get()
                 max=move || max.get()
                 step="0.01"
                 prop:value=value
@@ -0,0 +1,312 @@
+use leptos::{ev, prelude::*};
+use leptos_ethereum_provider::EthereumInterface;
+use leptos_router::hooks::{use_navigate, use_query_map};
+use leptos_use::{use_event_listener, use_window};
+use mandelbrot_explorer::{Bounds, FrameColor, ISample};
+use send_wrapper::SendWrapper;
+use wasm_bindgen::JsCast;
+
+use super::frame_control::is_bid_frame;
+use crate::{
+    context::{Context, ExplorerStoreFields, StateStoreFields, Tab},
+    coordinates::{pan_zoom, to_f64, width},
+    shortcuts::{Action, Binding, action_for, default_bindings, key_label, key_name},
+    util::{load_item, preserve_log_level, store_item},
+};
+
+// fraction of the view moved by one pan
+const PAN_STEP: f64 = 0.1;
+const ZOOM_STEP: f64 = 1.5;
+// smallest side of the focus outline in pixels
+const MIN_OUTLINE_SIZE: f64 = 6.0;
+
+#[derive(Clone)]
+struct FocusableFrame {
+    id: u128,
+    is_bid: bool,
+    bounds: Bounds,
+}
+
+/// Whether the key goes to a form field rather than the explorer.
+fn is_typing(ev: &web_sys::KeyboardEvent) -> bool {
+    ev.target()
+        .and_then(|target| target.dyn_into::<web_sys::HtmlElement>().ok())
+        .is_some_and(|element| {
+            matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT") || element.is_content_editable()
+        })
+}
+
+/// Whether nothing but the canvas or the page has focus, so that keys such as Tab, Enter and Space aren't taken from
+/// buttons and links.
+fn is_for_explorer(ev: &web_sys::KeyboardEvent, canvas: Option<web_sys::HtmlCanvasElement>) -> bool {
+    let Some(target) = ev.target() else {
+        return true;
+    };
+    let is_page = target
+        .dyn_ref::<web_sys::Element>()
+        .is_none_or(|element| matches!(element.tag_name().as_str(), "BODY" | "HTML"));
+    is_page || canvas.is_some() && target.dyn_into::<web_sys::HtmlCanvasElement>().ok() == canvas
+}
+
+#[component]
+pub fn Shortcuts() -> impl IntoView {
+    let context = use_context::<SendWrapper<Context>>().unwrap();
+    let canvas = use_context::<NodeRef<leptos::html::Canvas>>().unwrap();
+    let ethereum = use_context::<Option<EthereumInterface>>().unwrap();
+    let navigate = use_navigate();
+    let query_map = use_query_map();
+
+    let bindings = RwSignal::new(load_item::<Vec<Binding>>("key_bindings").unwrap_or_else(default_bindings));
+    Effect::new(move || store_item("key_bindings", &bindings.get()));
+
+    let help_open = RwSignal::new(false);
+    // action waiting for a key to be bound to it
+    let recording = RwSignal::new(None::<Action>);
+    let focused = RwSignal::new(None::<u128>);
+
+    // a new token has new frames
+    Effect::new({
+        let context = context.clone();
+        move || {
+            context.state.explorer().nav_history().track();
+            focused.set(None);
+        }
+    });
+
+    let focusable_frames = {
+        let context = context.clone();
+        move || {
+            let mut frames = context
+                .mandelbrot
+                .lock()
+                .unwrap()
+                .frames
+                .iter()
+                .filter(|frame| !matches!(frame.color, FrameColor::Blue | FrameColor::LightBlue))
+                .map(|frame| FocusableFrame {
+                    id: frame.id,
+                    is_bid: is_bid_frame(&frame.color),
+                    bounds: frame.bounds.clone(),
+                })
+                .collect::<Vec<_>>();
+            frames.sort_by_key(|frame| frame.id);
+            frames
+        }
+    };
+
+    let is_tab_available = move |tab: Tab| match tab {
+        Tab::Auction => ethereum.is_some(),
+        Tab::Inventory | Tab::Sales => ethereum.as_ref().is_some_and(|ethereum| ethereum.connected()),
+        _ => true,
+    };
+
+    let run = {
+        let context = context.clone();
+        let focusable_frames = focusable_frames.clone();
+        move |action: Action| {
+            let pan_zoom_view = |dx: f64, dy: f64, scale: f64| {
+                let bounds = context.mandelbrot.lock().unwrap().engine.borrow().get_bounds();
+                context
+                    .mandelbrot
+                    .lock()
+                    .unwrap()
+                    .move_into_bounds(&pan_zoom(&bounds, dx, dy, scale));
+            };
+            let enter_token = |token_id: u128, bounds: &Bounds| {
+                context.mandelbrot.lock().unwrap().move_into_bounds(bounds);
+                navigate(
+                    &preserve_log_level(format!("/tokens/{}", token_id), query_map),
+                    Default::default(),
+                );
+            };
+            let cycle = |step: isize| {
+                let frames = focusable_frames();
+                if frames.is_empty() {
+                    return;
+                }
+                let current = focused
+                    .get_untracked()
+                    .and_then(|id| frames.iter().position(|frame| frame.id == id));
+                let next = match current {
+                    Some(index) => (index as isize + step).rem_euclid(frames.len() as isize) as usize,
+                    None if step > 0 => 0,
+                    None => frames.len() - 1,
+                };
+                focused.set(Some(frames[next].id));
+            };
+            let focused_frame = || {
+                let id = focused.get_untracked()?;
+                focusable_frames().into_iter().find(|frame| frame.id == id)
+            };
+
+            match action {
+                Action::PanLeft => pan_zoom_view(-PAN_STEP, 0.0, 1.0),
+                Action::PanRight => pan_zoom_view(PAN_STEP, 0.0, 1.0),
+                Action::PanUp => pan_zoom_view(0.0, PAN_STEP, 1.0),
+                Action::PanDown => pan_zoom_view(0.0, -PAN_STEP, 1.0),
+                Action::ZoomIn => pan_zoom_view(0.0, 0.0, 1.0 / ZOOM_STEP),
+                Action::ZoomOut => pan_zoom_view(0.0, 0.0, ZOOM_STEP),
+                Action::Parent => {
+                    let parent = context.state.explorer().nav_history().with_untracked(|nav_history| {
+                        nav_history.len().checked_sub(2).map(|index| {
+                            (
+                                nav_history[index].token_id,
+                                nav_history[index].to_frame(FrameColor::Blue),
+                            )
+                        })
+                    });
+                    if let Some((token_id, frame)) = parent {
+                        enter_token(token_id, &frame.bounds);
+                    }
+                }
+                Action::NextFrame => cycle(1),
+                Action::PreviousFrame => cycle(-1),
+                Action::EnterFrame => {
+                    if let Some(frame) = focused_frame().filter(|frame| !frame.is_bid) {
+                        enter_token(frame.id, &frame.bounds);
+                    }
+                }
+                Action::ToggleBid => {
+                    if let Some(frame) = focused_frame().filter(|frame| frame.is_bid) {
+                        let selected = context
+                            .state
+                            .explorer()
+                            .bids()
+                            .with_untracked(|bids| bids.get(&frame.id).is_some_and(|bid| bid.selected));
+                        context.select_bid(frame.id, !selected);
+                    }
+                }
+                Action::SelectTab(position) => {
+                    if let Some(tab) = Tab::ALL.get((position as usize).wrapping_sub(1)).copied() {
+                        if is_tab_available(tab) {
+                            context.state.selected_tab().set(tab);
+                        }
+                    }
+                }
+                Action::Help => help_open.update(|help_open| *help_open = !*help_open),
+            }
+        }
+    };
+
+    let _ = use_event_listener(use_window(), ev::keydown, move |ev| {
+        if is_typing(&ev) {
+            return;
+        }
+        let Some(key) = key_name(&ev) else {
+            return;
+        };
+        if let Some(action) = recording.get_untracked() {
+            ev.prevent_default();
+            if key != "Escape" {
+                bindings.update(|bindings| {
+                    // a key only triggers one action
+                    bindings
+                        .iter_mut()
+                        .for_each(|binding| binding.keys.retain(|bound| *bound != key));
+                    if let Some(binding) = bindings.iter_mut().find(|binding| binding.action == action) {
+                        binding.keys.push(key);
+                    }
+                });
+            }
+            recording.set(None);
+            return;
+        }
+        if key == "Escape" && help_open.get_untracked() {
+            help_open.set(false);
+            return;
+        }
+        if !is_for_explorer(&ev, canvas.get_untracked()) {
+            return;
+        }
+        if let Some(action) = bindings.with_untracked(|bindings| action_for(bindings, &key)) {
+            ev.prevent_default();
+            run(action);
+        }
+    });
+
+    // screen position of the focused frame
+    let outline = move || {
+        let id = focused.get()?;
+        context.state.focus().track();
+        let canvas = canvas.get()?;
+        let frame = focusable_frames().into_iter().find(|frame| frame.id == id)?;
+        let view = context.mandelbrot.lock().unwrap().engine.borrow().get_bounds();
+        let rect = canvas.get_bounding_client_rect();
+        let scale = rect.width() / to_f64(&width(&view));
+        let left = rect.left() + to_f64(&(&frame.bounds.x_min - view.x_min.clone())) * scale;
+        let top = rect.top() + to_f64(&(&view.y_max - frame.bounds.y_max.clone())) * scale;
+        let frame_width = (to_f64(&width(&frame.bounds)) * scale).max(MIN_OUTLINE_SIZE);
+        let frame_height = (to_f64(&(&frame.bounds.y_max - frame.bounds.y_min.clone())) * scale).max(MIN_OUTLINE_SIZE);
+        Some((left, top, frame_width, frame_height, frame.id, frame.is_bid))
+    };
+
+    view! {
+        {move || outline().map(|(left, top, width, height, id, is_bid)| view! {
+            <div
+                class="fixed z-30 pointer-events-none border-2 border-dashed border-white rounded-sm"
+                style=format!("left: {}px; top: {}px; width: {}px; height: {}px;", left, top, width, height)
+            >
+                <span class="absolute -top-5 left-0 px-1 rounded bg-gray-900/80 text-white text-xs whitespace-nowrap">
+                    {if is_bid { format!("Bid #{}", id) } else { format!("NFT #{}", id) }}
+                </span>
+            </div>
+        })}
+
+        <Show when=move || help_open.get()>
+            <div class="fixed inset-0 z-50 flex items-center justify-center bg-black/50" on:click=move |_| help_open.set(false)>
+                <div
+                    class="w-full max-w-lg max-h-[80vh] overflow-y-auto bg-gray-900 text-white rounded-lg shadow-xl p-4 space-y-3"
+                    on:click=|ev| ev.stop_propagation()
+                >
+                    <div class="flex items-center justify-between">
+                        <h2 class="text-lg font-semibold">"Keyboard shortcuts"</h2>
+                        <button
+                            on:click=move |_| bindings.set(default_bindings())
+                            class="px-3 py-1 bg-gray-700 hover:bg-gray-600 rounded-md text-sm font-medium transition"
+                        >
+                            "Reset"
+                        </button>
+                    </div>
+                    <table class="w-full text-sm">
+                        <tbody>
+                            {move || bindings.get().into_iter().map(|binding| {
+                                let action = binding.action;
+                                view! {
+                                    <tr class="border-b border-gray-700">
+                                        <td class="py-1 pr-2">{action.label()}</td>
+                                        <td class="py-1 flex flex-wrap items-center gap-1">
+                                            {binding.keys.into_iter().map(|key| view! {
+                                                <button
+                                                    on:click={
+                                                        let key = key.clone();
+                                                        move |_| bindings.update(|bindings| {
+                                                            bindings
+                                                                .iter_mut()
+                                                                .filter(|binding| binding.action == action)
+                                                                .for_each(|binding| binding.keys.retain(|bound| *bound != key));
+                                                        })
+                                                    }
+                                                    class="px-2 rounded bg-gray-700 hover:bg-red-700 font-mono"
+                                                    title="Remove"
+                                                >
+                                                    {key_label(&key)}
+                                                </button>
+                                            }).collect_view()}
+                                            <button
+                                                on:click=move |_| recording.set(Some(action))
+                                                class="px-2 rounded border border-gray-600 hover:bg-gray-700 text-xs"
+                                            >
+                                                {move || if recording.get() == Some(action) { "Press a key…" } else { "Add" }}
+                                            </button>
+                                        </td>
+                                    </tr>
+                                }
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                    <p class="text-xs text-gray-400">"Click a key to remove it. Shortcuts are ignored while typing in a field."</p>
+                </div>
+            </div>
+        </Show>
+    }
+}
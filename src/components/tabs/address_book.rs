@@ -0,0 +1,111 @@
+use leptos::{prelude::*, task::spawn_local};
+use send_wrapper::SendWrapper;
+use web3::types::Address;
+
+use crate::{
+    address_book::{self, Contact},
+    components::primitive::{AddressTag, ContactEditor},
+    context::{Context, StateStoreFields},
+    util::{download_file, read_file_text},
+};
+
+#[component]
+pub fn AddressBook() -> impl IntoView {
+    let context = use_context::<SendWrapper<Context>>().unwrap();
+    let address_book = context.state.address_book();
+    let new_address = RwSignal::new(String::new());
+    let new_contact = RwSignal::new(None);
+    let import_failed = RwSignal::new(false);
+
+    let parsed_address =
+        Memo::new(move |_| new_address.with(|address| address.trim().trim_start_matches("0x").parse::<Address>().ok()));
+
+    let sorted_contacts = Memo::new(move |_| {
+        let mut contacts = address_book.get().into_iter().collect::<Vec<_>>();
+        contacts.sort_by(|(_, a), (_, b)| a.label.to_lowercase().cmp(&b.label.to_lowercase()));
+        contacts
+    });
+
+    let export = move |_| {
+        download_file(
+            "address-book.json",
+            "application/json",
+            address_book::export(&address_book.get_untracked()).as_bytes(),
+        );
+    };
+
+    let import = move |ev: web_sys::Event| {
+        let input = event_target::<web_sys::HtmlInputElement>(&ev);
+        if let Some(file) = input.files().and_then(|files| files.get(0)) {
+            spawn_local(async move {
+                match read_file_text(&file).await.and_then(|json| address_book::import(&json)) {
+                    Some(imported) => {
+                        import_failed.set(false);
+                        address_book.update(|address_book| address_book.extend(imported));
+                    }
+                    None => import_failed.set(true),
+                }
+            });
+        }
+        input.set_value("");
+    };
+
+    view! {
+        <div class="space-y-4">
+            <div class="flex flex-row gap-2 items-center">
+                <input
+                    type="text"
+                    placeholder="0x..."
+                    class="flex-1 px-2 py-1 rounded-md border border-gray-300 font-mono text-sm"
+                    prop:value=move || new_address.get()
+                    on:input=move |ev| new_address.set(event_target_value(&ev))
+                />
+                <button
+                    disabled=move || parsed_address.get().is_none()
+                    on:click=move |_| new_contact.set(Some(Contact::default()))
+                    class="px-4 py-2 bg-green-600 hover:bg-green-500 disabled:opacity-50 rounded-md text-white text-sm font-semibold transition"
+                >
+                    "Add"
+                </button>
+            </div>
+            {move || parsed_address.get().map(|address| view! { <ContactEditor address contact=new_contact/> })}
+
+            <div class="p-4 bg-gray-900 rounded-md space-y-2">
+                <For
+                    each=move || sorted_contacts.get()
+                    key=|(address, contact)| (*address, contact.label.clone(), contact.color.clone(), contact.notes.clone())
+                    let((address, contact))
+                >
+                    <div class="flex items-center justify-between gap-4 bg-gray-800 text-white rounded px-4 py-2">
+                        <div class="flex flex-col text-sm">
+                            <AddressTag address/>
+                            <span class="text-xs text-gray-400 whitespace-pre-wrap">{contact.notes}</span>
+                        </div>
+                        <button
+                            on:click=move |_| address_book.update(|address_book| { address_book.remove(&address); })
+                            class="px-3 py-1 bg-red-600 hover:bg-red-500 rounded-md text-white text-sm font-medium transition"
+                        >
+                            "Delete"
+                        </button>
+                    </div>
+                </For>
+            </div>
+
+            <div class="flex flex-row gap-2 items-center">
+                <button
+                    on:click=export
+                    class="px-4 py-2 bg-gray-700 hover:bg-gray-600 text-white rounded-md text-sm font-semibold transition"
+                >
+                    "Export"
+                </button>
+                <label class="px-4 py-2 bg-gray-700 hover:bg-gray-600 text-white rounded-md text-sm font-semibold transition cursor-pointer">
+                    "Import"
+                    <input type="file" accept="application/json,.json" class="hidden" on:change=import/>
+                </label>
+                <Show when=move || import_failed.get()>
+                    <span class="text-sm text-red-500">"Unable to read the address book file"</span>
+                </Show>
+            </div>
+        </div>
+    }
+}
@@ -4,7 +4,7 @@ use leptos::prelude::*;
 use mandelbrot_explorer::FrameColor;
 use send_wrapper::SendWrapper;
 
-use crate::{context::Context, evm::types::Metadata};
+use crate::{components::primitive::AddressTag, context::Context, evm::types::Metadata};
 
 #[component]
 pub fn Bids<T>(bids: T) -> impl IntoView
@@ -40,8 +40,9 @@ where
                         let zoom_bid = zoom_bid.clone();
                         view! {
                             <div class="flex items-center justify-between bg-gray-800 text-white rounded px-4 py-2">
-                                <span class="text-sm font-mono">
-                                    {format!("{} {:?}", bid.locked_tokens.to_string(), bid.owner)}
+                                <span class="flex items-center gap-2 text-sm font-mono">
+                                    {bid.locked_tokens.to_string()}
+                                    <AddressTag address=bid.owner/>
                                 </span>
                                 <button
                                     on:click=move |_| zoom_bid(bid.token_id)
@@ -1,13 +1,37 @@
 use leptos::prelude::*;
+use send_wrapper::SendWrapper;
 
-use crate::evm::types::Metadata;
+use crate::{
+    components::primitive::AddressTag,
+    context::{Context, StateStoreFields},
+    evm::types::Metadata,
+};
 
 #[component]
 pub fn Info(token: Metadata) -> impl IntoView {
+    let context = use_context::<SendWrapper<Context>>().unwrap();
+    let watchlist = context.state.watchlist();
+    let token_id = token.token_id;
+    let watched = move || watchlist.with(|watchlist| watchlist.tokens.contains(&token_id));
+
     view! {
-        <p>{format!("NFT id: {}", token.token_id)}</p>
-        <p>{format!("Owner: {}", token.owner)}</p>
-        <p>{format!("Locked OM: {}", token.locked_tokens)}</p>
-        <p>{format!("Minimum bid: {}", token.minimum_price)}</p>
+        <div class="flex items-start justify-between">
+            <div>
+                <p>{format!("NFT id: {}", token.token_id)}</p>
+                <p>"Owner: "<AddressTag address=token.owner/></p>
+                <p>{format!("Locked OM: {}", token.locked_tokens)}</p>
+                <p>{format!("Minimum bid: {}", token.minimum_price)}</p>
+            </div>
+            <button
+                on:click=move |_| watchlist.update(|watchlist| {
+                    if !watchlist.tokens.remove(&token_id) {
+                        watchlist.tokens.insert(token_id);
+                    }
+                })
+                class="px-3 py-1 bg-gray-700 hover:bg-gray-600 rounded-md text-white text-sm font-medium transition"
+            >
+                {move || if watched() { "Unwatch" } else { "Watch" }}
+            </button>
+        </div>
     }
 }
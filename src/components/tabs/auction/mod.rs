@@ -1,6 +1,7 @@
 mod bids;
 mod form;
 mod info;
+mod timeline;
 
 use leptos::prelude::*;
 use leptos_router::{
@@ -12,7 +13,7 @@ use leptos_router::{
 use send_wrapper::SendWrapper;
 
 use crate::context::{Context, ExplorerStoreFields, StateStoreFields};
-use {bids::Bids, form::Form, info::Info};
+use {bids::Bids, form::Form, info::Info, timeline::Timeline};
 
 #[component]
 pub fn Auction() -> impl IntoView {
@@ -71,7 +72,12 @@ fn Controller() -> impl IntoView {
                             <div class="bg-gray-800 text-white rounded-md shadow p-4">
                                 <Bids bids=context.state.explorer().bids() />
                             </div>
+                            <div class="border-t border-gray-700 my-4" />
                         </Show>
+
+                        <div class="bg-gray-800 text-white rounded-md shadow p-4">
+                            <Timeline token_id=token.token_id />
+                        </div>
                     }.into_any()
                 })
             }
@@ -0,0 +1,174 @@
+use std::collections::HashSet;
+
+use leptos::prelude::*;
+use send_wrapper::SendWrapper;
+
+use crate::{
+    activity::{Activity, ActivityKind, timeline, transaction_link, unattributed_burns, with_minimum_price_changes},
+    components::primitive::AddressTag,
+    context::{Context, ExplorerStoreFields, StateStoreFields},
+    util::format_timestamp,
+};
+
+#[component]
+pub fn Timeline(token_id: u128) -> impl IntoView {
+    let context = use_context::<SendWrapper<Context>>().unwrap();
+    let activities = RwSignal::new(Vec::<Activity>::new());
+    let failed = RwSignal::new(false);
+    let shown_kinds = RwSignal::new(ActivityKind::ALL.into_iter().collect::<HashSet<_>>());
+
+    let complete = RwSignal::new(false);
+
+    // `true` loads another page of older blocks
+    let load = Action::new_local(move |older: &bool| {
+        let context = context.clone();
+        let older = *older;
+        async move {
+            match context.contract.get_nft_history(older).await {
+                Ok(history) => {
+                    let mut bids = context
+                        .state
+                        .explorer()
+                        .bids()
+                        .get_untracked()
+                        .into_keys()
+                        .collect::<HashSet<_>>();
+                    let mut children = context
+                        .state
+                        .explorer()
+                        .children()
+                        .get_untracked()
+                        .into_keys()
+                        .collect::<HashSet<_>>();
+                    let burns = unattributed_burns(token_id, &bids, &children, &history.transfers);
+                    for (past_bids, past_children) in context.contract.get_past_relations(token_id, burns).await {
+                        bids.extend(past_bids);
+                        children.extend(past_children);
+                    }
+                    let loaded = timeline(token_id, &bids, &children, &history.transfers);
+                    let changes = context
+                        .contract
+                        .get_minimum_price_changes(
+                            token_id,
+                            history.blocks.clone(),
+                            loaded.iter().map(|activity| activity.block_number),
+                        )
+                        .await;
+                    let mut loaded = with_minimum_price_changes(loaded, &changes);
+                    let timestamps = context
+                        .contract
+                        .get_block_timestamps(loaded.iter().map(|activity| activity.block_number))
+                        .await;
+                    for activity in &mut loaded {
+                        activity.timestamp = timestamps.get(&activity.block_number).copied();
+                    }
+                    failed.set(false);
+                    complete.set(history.complete);
+                    activities.set(loaded);
+                }
+                Err(error) => {
+                    log::error!("Unable to load the activity of NFT {}: {:?}", token_id, error);
+                    failed.set(true);
+                }
+            }
+        }
+    });
+    load.dispatch(false);
+
+    let filtered_activities = Memo::new(move |_| {
+        let shown_kinds = shown_kinds.get();
+        activities.with(|activities| {
+            activities
+                .iter()
+                .filter(|activity| shown_kinds.contains(&activity.kind))
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+    });
+
+    view! {
+        <div class="space-y-4">
+            <div class="flex items-center justify-between">
+                <p class="text-lg font-semibold text-white">"Activity:"</p>
+                <button
+                    disabled=move || load.pending().get()
+                    on:click=move |_| { load.dispatch(false); }
+                    class="px-3 py-1 bg-gray-700 hover:bg-gray-600 disabled:opacity-50 rounded-md text-white text-sm font-medium transition"
+                >
+                    "Refresh"
+                </button>
+            </div>
+
+            <div class="flex flex-wrap gap-x-4 gap-y-1">
+                {ActivityKind::ALL.into_iter().map(|kind| view! {
+                    <label class="flex items-center gap-2 text-xs text-gray-300 cursor-pointer">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || shown_kinds.with(|shown_kinds| shown_kinds.contains(&kind))
+                            on:change=move |ev| shown_kinds.update(|shown_kinds| {
+                                if event_target_checked(&ev) {
+                                    shown_kinds.insert(kind);
+                                } else {
+                                    shown_kinds.remove(&kind);
+                                }
+                            })
+                            class="accent-accent1 w-4 h-4"
+                        />
+                        {kind.label()}
+                    </label>
+                }).collect_view()}
+            </div>
+
+            <div class="p-4 bg-gray-900 rounded-md space-y-2">
+                <Show when=move || load.pending().get()>
+                    <p class="text-sm text-gray-400">"Loading activity…"</p>
+                </Show>
+                <Show when=move || failed.get()>
+                    <p class="text-sm text-red-500">"Unable to load the activity of this NFT"</p>
+                </Show>
+                <Show when=move || !load.pending().get() && !failed.get() && filtered_activities.with(|activities| activities.is_empty())>
+                    <p class="text-sm text-gray-400">
+                        {move || if complete.get() { "No activity" } else { "No activity in recent blocks" }}
+                    </p>
+                </Show>
+                <For
+                    each=move || filtered_activities.get()
+                    key=|activity| (activity.tx_hash, activity.description.clone())
+                    let(activity)
+                >
+                    <div class="flex items-center justify-between gap-4 bg-gray-800 text-white rounded px-4 py-2">
+                        <div class="flex flex-col text-sm">
+                            <span class="font-semibold">{activity.description}</span>
+                            <span class="flex items-center gap-2 text-xs text-gray-400">
+                                {match activity.timestamp {
+                                    Some(timestamp) => format_timestamp(timestamp as f64 * 1000.0),
+                                    None => format!("Block {}", activity.block_number),
+                                }}
+                                <AddressTag address=activity.address/>
+                            </span>
+                        </div>
+                        {transaction_link(&activity.tx_hash).map(|link| view! {
+                            <a
+                                href=link
+                                target="_blank"
+                                rel="noopener noreferrer"
+                                class="text-xs text-blue-400 hover:text-blue-300"
+                            >
+                                "Transaction ↗"
+                            </a>
+                        })}
+                    </div>
+                </For>
+                <Show when=move || !complete.get() && !failed.get()>
+                    <button
+                        disabled=move || load.pending().get()
+                        on:click=move |_| { load.dispatch(true); }
+                        class="w-full px-3 py-1 bg-gray-700 hover:bg-gray-600 disabled:opacity-50 rounded-md text-white text-sm font-medium transition"
+                    >
+                        "Load older activity"
+                    </button>
+                </Show>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,264 @@
+use leptos::{prelude::*, task::spawn_local};
+use mandelbrot_explorer::{Focus, ISample};
+use send_wrapper::SendWrapper;
+use wasm_bindgen::JsCast;
+
+use crate::{
+    context::{Context, RenderSettingsStoreFields, StateStoreFields},
+    coordinates::magnification,
+    snapshot::{
+        FOCUS_KEY, OffscreenRenderer, PALETTE_KEY, TILE_SIZE, TOKEN_ID_KEY, Viewport, is_supported_size, resolutions,
+        text_chunk, tiles, with_text_chunks,
+    },
+    util::{download_file, read_file_bytes},
+};
+
+use super::visuals::Palette;
+
+/// Focus, palette and token id embedded in an exported image.
+pub struct Snapshot {
+    pub focus: Focus,
+    pub palette: Option<Palette>,
+    pub token_id: Option<u128>,
+}
+
+impl Snapshot {
+    fn read(png: &[u8]) -> Option<Self> {
+        Some(Self {
+            focus: text_chunk(png, FOCUS_KEY)?.parse().ok()?,
+            palette: text_chunk(png, PALETTE_KEY).and_then(|palette| serde_json::from_str(&palette).ok()),
+            token_id: text_chunk(png, TOKEN_ID_KEY).and_then(|token_id| token_id.parse().ok()),
+        })
+    }
+}
+
+fn create_canvas(width: u32, height: u32) -> Option<(web_sys::HtmlCanvasElement, web_sys::CanvasRenderingContext2d)> {
+    let canvas = web_sys::window()?
+        .document()?
+        .create_element("canvas")
+        .ok()?
+        .dyn_into::<web_sys::HtmlCanvasElement>()
+        .ok()?;
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let ctx = canvas
+        .get_context("2d")
+        .ok()??
+        .dyn_into::<web_sys::CanvasRenderingContext2d>()
+        .ok()?;
+    Some((canvas, ctx))
+}
+
+/// Render a PNG image tile by tile on `renderer`, the browser updates while the engine draws each tile.
+/// `on_progress` receives the fraction of tiles rendered so far and returns whether to carry on, the image is `None`
+/// when cancelled or failed.
+pub(super) async fn render_png(
+    renderer: &OffscreenRenderer,
+    viewport: &Viewport,
+    width: u32,
+    height: u32,
+    on_progress: impl Fn(f64) -> bool,
+) -> Option<Vec<u8>> {
+    use base64::{Engine, engine::general_purpose::STANDARD};
+
+    let (canvas, ctx) = create_canvas(width, height)?;
+    let size = renderer.size();
+    let tiles = tiles(width, height, size);
+    for (index, (left, top, tile_width, tile_height)) in tiles.iter().copied().enumerate() {
+        if !on_progress(index as f64 / tiles.len() as f64) {
+            return None;
+        }
+
+        // tiles at the right and bottom edges only use part of the square
+        let tile = renderer.render_bounds(&viewport.square(left, top, size)).await;
+        ctx.draw_image_with_html_canvas_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+            tile,
+            0.0,
+            0.0,
+            tile_width as f64,
+            tile_height as f64,
+            left as f64,
+            top as f64,
+            tile_width as f64,
+            tile_height as f64,
+        )
+        .ok()?;
+    }
+
+    let data_url = canvas.to_data_url_with_type("image/png").ok()?;
+    STANDARD.decode(data_url.split_once(',')?.1).ok()
+}
+
+#[component]
+pub fn ImageExport(palette: RwSignal<Palette>, on_open: impl Fn(String, Snapshot) + Clone + 'static) -> impl IntoView {
+    let context = use_context::<SendWrapper<Context>>().unwrap();
+    let width = RwSignal::new(3840u32);
+    let height = RwSignal::new(2160u32);
+    // fraction of tiles rendered while exporting
+    let progress = RwSignal::new(None::<f64>);
+    let cancelled = RwSignal::new(false);
+    let export_failed = RwSignal::new(false);
+    let open_failed = RwSignal::new(false);
+
+    let export = move |_| {
+        let (width, height) = (width.get_untracked(), height.get_untracked());
+        let (focus, bounds) = {
+            let mandelbrot = context.mandelbrot.lock().unwrap();
+            let engine = mandelbrot.engine.borrow();
+            (engine.focus(), engine.get_bounds())
+        };
+        let token_id = context.state.current_token_id().get_untracked();
+        let engine = context.state.render_settings().engine().get_untracked();
+        let palette = palette.get_untracked();
+        spawn_local(async move {
+            export_failed.set(false);
+            cancelled.set(false);
+            let Some(renderer) =
+                OffscreenRenderer::acquire(engine, TILE_SIZE, &palette.coloring(magnification(&bounds)))
+            else {
+                export_failed.set(true);
+                return;
+            };
+            let png = render_png(
+                &renderer,
+                &Viewport::new(&bounds, width, height),
+                width,
+                height,
+                |fraction| {
+                    progress.set(Some(fraction));
+                    !cancelled.get_untracked()
+                },
+            )
+            .await;
+            progress.set(None);
+            if cancelled.get_untracked() {
+                return;
+            }
+
+            let mut texts = vec![
+                (FOCUS_KEY, focus.to_string()),
+                (PALETTE_KEY, serde_json::to_string(&palette).unwrap()),
+            ];
+            if let Some(token_id) = token_id {
+                texts.push((TOKEN_ID_KEY, token_id.to_string()));
+            }
+            match png.and_then(|png| with_text_chunks(&png, &texts)) {
+                Some(png) => download_file(&format!("mandelbrot-{}x{}.png", width, height), "image/png", &png),
+                None => export_failed.set(true),
+            }
+        });
+    };
+
+    let open_file = move |file: web_sys::File| {
+        let on_open = on_open.clone();
+        spawn_local(async move {
+            match read_file_bytes(&file).await.as_deref().and_then(Snapshot::read) {
+                Some(snapshot) => {
+                    open_failed.set(false);
+                    let name = file
+                        .name()
+                        .rsplit_once('.')
+                        .map_or(file.name(), |(name, _)| name.to_string());
+                    on_open(name, snapshot);
+                }
+                None => open_failed.set(true),
+            }
+        });
+    };
+
+    let size_input = move |label: &'static str, value: RwSignal<u32>| {
+        view! {
+            <label class="flex items-center gap-2 text-sm text-gray-700">
+                {label}
+                <input
+                    type="number"
+                    min="1"
+                    prop:value=move || value.get().to_string()
+                    on:change=move |ev| {
+                        if let Ok(size) = event_target_value(&ev).parse() {
+                            value.set(size);
+                        }
+                    }
+                    class="w-24 px-2 py-1 rounded-md border border-gray-300"
+                />
+            </label>
+        }
+    };
+
+    view! {
+        <div class="px-4 py-2 bg-gray-100 shadow-sm space-y-2">
+            <div class="flex flex-row flex-wrap items-center gap-2">
+                <select
+                    on:change=move |ev| {
+                        if let Some(resolution) = resolutions().into_iter().find(|resolution| resolution.name == event_target_value(&ev)) {
+                            width.set(resolution.width);
+                            height.set(resolution.height);
+                        }
+                    }
+                    class="px-2 py-1 rounded-md border border-gray-300 text-sm"
+                >
+                    <option value="" selected>"Custom"</option>
+                    {resolutions().into_iter().map(|resolution| view! {
+                        <option value=resolution.name>
+                            {format!("{} ({}×{})", resolution.name, resolution.width, resolution.height)}
+                        </option>
+                    }).collect_view()}
+                </select>
+                {size_input("Width", width)}
+                {size_input("Height", height)}
+            </div>
+
+            <div class="flex flex-row flex-wrap items-center gap-2">
+                <Show
+                    when=move || progress.get().is_some()
+                    fallback=move || view! {
+                        <button
+                            on:click=export.clone()
+                            disabled=move || !is_supported_size(width.get(), height.get())
+                            class="px-4 py-2 bg-blue-600 hover:bg-blue-500 disabled:opacity-50 text-white rounded-md text-sm font-semibold transition"
+                        >
+                            "Export PNG"
+                        </button>
+                    }
+                >
+                    <progress max="1" value=move || progress.get().unwrap_or_default() class="w-40"/>
+                    <span class="text-sm font-mono text-gray-700">
+                        {move || format!("{:.0}%", progress.get().unwrap_or_default() * 100.0)}
+                    </span>
+                    <button
+                        on:click=move |_| cancelled.set(true)
+                        class="px-4 py-2 bg-red-600 hover:bg-red-500 text-white rounded-md text-sm font-semibold transition"
+                    >
+                        "Cancel"
+                    </button>
+                </Show>
+                <label class="px-4 py-2 bg-gray-700 hover:bg-gray-600 text-white rounded-md text-sm font-semibold transition cursor-pointer">
+                    "Open image"
+                    <input
+                        type="file"
+                        accept=".png"
+                        class="hidden"
+                        on:change=move |ev| {
+                            let input = event_target::<web_sys::HtmlInputElement>(&ev);
+                            if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                                open_file(file);
+                            }
+                            input.set_value("");
+                        }
+                    />
+                </label>
+            </div>
+
+            <Show when=move || !is_supported_size(width.get(), height.get())>
+                <p class="text-sm text-red-500">"Browsers can't create images this large"</p>
+            </Show>
+            <p class="text-xs text-gray-500">"Frames aren't drawn on exported images"</p>
+            <Show when=move || export_failed.get()>
+                <p class="text-sm text-red-500">"Unable to export the image"</p>
+            </Show>
+            <Show when=move || open_failed.get()>
+                <p class="text-sm text-red-500">"This image has no location embedded"</p>
+            </Show>
+        </div>
+    }
+}
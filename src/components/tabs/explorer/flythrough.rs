@@ -0,0 +1,581 @@
+use leptos::{prelude::*, task::spawn_local};
+use leptos_use::{UseRafFnCallbackArgs, UseRafFnOptions, use_raf_fn_with_options};
+use mandelbrot_explorer::{BigFloat, Bounds, Focus};
+use send_wrapper::SendWrapper;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    archive::{MAX_FILES, ZipWriter},
+    context::{Context, RenderSettingsStoreFields, StateStoreFields},
+    coordinates::{center, log2, magnification, pow2, width},
+    snapshot::{OffscreenRenderer, TILE_SIZE, Viewport, is_supported_size},
+    util::{download_parts, load_item, store_item},
+};
+
+use super::{export::render_png, locations::SavedLocation, visuals::Palette};
+
+#[derive(Clone, Copy, Default, Deserialize, PartialEq, Serialize)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    #[default]
+    EaseInOut,
+}
+
+impl Easing {
+    pub const ALL: [Self; 4] = [Self::Linear, Self::EaseIn, Self::EaseOut, Self::EaseInOut];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Linear => "Linear",
+            Self::EaseIn => "Ease in",
+            Self::EaseOut => "Ease out",
+            Self::EaseInOut => "Ease in-out",
+        }
+    }
+
+    fn apply(&self, t: f64) -> f64 {
+        match self {
+            Self::Linear => t,
+            Self::EaseIn => t * t * t,
+            Self::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Self::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// A location to fly to, reached `duration` seconds after the previous keyframe.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct Keyframe {
+    pub name: String,
+    pub focus: Focus,
+    pub palette: Option<Palette>,
+    pub duration: f64,
+    pub easing: Easing,
+    /// Bounds shown for `focus`, found by an offscreen engine once the keyframe has been added or loaded.
+    #[serde(skip)]
+    pub bounds: Option<Bounds>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct Sequence {
+    pub name: String,
+    pub keyframes: Vec<Keyframe>,
+}
+
+/// Bounds `t` of the way from `from` to `to`. The size changes exponentially, so that zooming looks steady, and the
+/// center moves along with the size, so that the destination stays in place on the screen while zooming into it.
+/// Everything is kept in full precision, as keyframes can be deeper than f64 reaches.
+fn interpolate(from: &Bounds, to: &Bounds, t: f64) -> Bounds {
+    let (from_width, to_width) = (width(from), width(to));
+    let (from_height, to_height) = (&from.y_max - from.y_min.clone(), &to.y_max - to.y_min.clone());
+    let zoom = log2(&(to_width / from_width.clone()));
+    let scale = pow2(zoom * t);
+    let height_scale = pow2(log2(&(to_height / from_height.clone())) * t);
+    // share of the way between the centers still ahead, anchored on the destination
+    let remaining = if zoom.abs() > 1e-9 {
+        let ratio = pow2(zoom);
+        (&scale - ratio.clone()) / (&BigFloat::from(1.0) - ratio)
+    } else {
+        BigFloat::from(1.0 - t)
+    };
+
+    let ((from_x, from_y), (to_x, to_y)) = (center(from), center(to));
+    let x = &to_x + &((&from_x - to_x.clone()) * remaining.clone());
+    let y = &to_y + &((&from_y - to_y.clone()) * remaining);
+    let half_width = from_width * scale * BigFloat::from(0.5);
+    let half_height = from_height * height_scale * BigFloat::from(0.5);
+    Bounds {
+        x_min: &x - half_width.clone(),
+        y_min: &y - half_height.clone(),
+        x_max: &x + &half_width,
+        y_max: &y + &half_height,
+    }
+}
+
+/// Keyframes resolved into bounds and palettes, ready to be interpolated.
+struct Path {
+    bounds: Vec<Bounds>,
+    palettes: Vec<Palette>,
+    keyframes: Vec<Keyframe>,
+}
+
+impl Path {
+    /// `None` with fewer than two keyframes or until the bounds of every keyframe are known. Keyframes without a
+    /// palette keep the one before them.
+    fn resolve(keyframes: Vec<Keyframe>, palette: Palette) -> Option<Self> {
+        if keyframes.len() < 2 {
+            return None;
+        }
+        let bounds = keyframes
+            .iter()
+            .map(|keyframe| keyframe.bounds.clone())
+            .collect::<Option<Vec<_>>>()?;
+        let palettes = keyframes
+            .iter()
+            .scan(palette, |palette, keyframe| {
+                if let Some(keyframe_palette) = &keyframe.palette {
+                    *palette = keyframe_palette.clone();
+                }
+                Some(palette.clone())
+            })
+            .collect();
+        Some(Self {
+            bounds,
+            palettes,
+            keyframes,
+        })
+    }
+
+    fn duration(&self) -> f64 {
+        self.keyframes.iter().skip(1).map(|keyframe| keyframe.duration).sum()
+    }
+
+    fn at(&self, mut time: f64) -> (Bounds, Palette) {
+        for index in 1..self.keyframes.len() {
+            let keyframe = &self.keyframes[index];
+            if time < keyframe.duration || index == self.keyframes.len() - 1 {
+                let t = if keyframe.duration > 0.0 {
+                    (time / keyframe.duration).clamp(0.0, 1.0)
+                } else {
+                    1.0
+                };
+                let t = keyframe.easing.apply(t);
+                return (
+                    interpolate(&self.bounds[index - 1], &self.bounds[index], t),
+                    self.palettes[index - 1].blend(&self.palettes[index], t),
+                );
+            }
+            time -= keyframe.duration;
+        }
+        (self.bounds[0].clone(), self.palettes[0].clone())
+    }
+}
+
+#[component]
+pub fn Flythrough(
+    locations: RwSignal<Vec<SavedLocation>>,
+    active_palette: RwSignal<Palette>,
+    selected_palette: RwSignal<Palette>,
+) -> impl IntoView {
+    let context = use_context::<SendWrapper<Context>>().unwrap();
+    let canvas = use_context::<NodeRef<leptos::html::Canvas>>().unwrap();
+    let keyframes = RwSignal::new(Vec::<Keyframe>::new());
+    let sequences = RwSignal::new(load_item::<Vec<Sequence>>("flythroughs").unwrap_or_default());
+    let sequence_name = RwSignal::new(String::new());
+    let location_id = RwSignal::new(None::<u64>);
+    let playing = RwSignal::new(false);
+    let width = RwSignal::new(1920u32);
+    let height = RwSignal::new(1080u32);
+    let fps = RwSignal::new(30u32);
+    // fraction of frames rendered while exporting
+    let progress = RwSignal::new(None::<f64>);
+    let cancelled = RwSignal::new(false);
+    // the frames don't fit into a ZIP archive
+    let too_large = RwSignal::new(false);
+
+    Effect::new(move || store_item("flythroughs", &sequences.get()));
+
+    let path = StoredValue::new_local(None::<Path>);
+    let elapsed = StoredValue::new(0.0);
+    let shown_palette = StoredValue::new(None::<Palette>);
+    let animation = use_raf_fn_with_options(
+        {
+            let mandelbrot = context.mandelbrot.clone();
+            move |UseRafFnCallbackArgs { delta, .. }| {
+                elapsed.update_value(|elapsed| *elapsed += delta / 1000.0);
+                let Some((bounds, palette, finished)) = path.with_value(|path| {
+                    path.as_ref().map(|path| {
+                        let (bounds, palette) = path.at(elapsed.get_value());
+                        (bounds, palette, elapsed.get_value() >= path.duration())
+                    })
+                }) else {
+                    return;
+                };
+
+                let mut mandelbrot = mandelbrot.lock().unwrap();
+                palette.apply(&mut mandelbrot.palette, magnification(&bounds));
+                mandelbrot.move_into_bounds(&bounds);
+                if let Some(redraw) = &mandelbrot.redraw {
+                    redraw();
+                }
+                shown_palette.set_value(Some(palette));
+                if finished {
+                    playing.set(false);
+                }
+            }
+        },
+        UseRafFnOptions::default().immediate(false),
+    );
+
+    Effect::new({
+        let pause = animation.pause.clone();
+        move |was_playing: Option<bool>| {
+            let is_playing = playing.get();
+            if !is_playing && was_playing == Some(true) {
+                pause();
+                // hand the palette the flythrough ended with over to the editor
+                if let Some(palette) = shown_palette.get_value() {
+                    selected_palette.set(palette);
+                }
+            }
+            is_playing
+        }
+    });
+
+    // bounds are only known to an engine, so an offscreen one finds them at the canvas size for each keyframe,
+    // leaving the view alone
+    let locating = StoredValue::new(false);
+    let located =
+        Memo::new(move |_| keyframes.with(|keyframes| keyframes.iter().all(|keyframe| keyframe.bounds.is_some())));
+    Effect::new({
+        let context = context.clone();
+        move || {
+            if locating.get_value() {
+                return;
+            }
+            let Some(focus) = keyframes.with(|keyframes| {
+                keyframes
+                    .iter()
+                    .find(|keyframe| keyframe.bounds.is_none())
+                    .map(|keyframe| keyframe.focus.clone())
+            }) else {
+                return;
+            };
+            let size = canvas.get_untracked().map_or(TILE_SIZE, |canvas| canvas.width());
+            let engine = context.state.render_settings().engine().get_untracked();
+            locating.set_value(true);
+            spawn_local(async move {
+                let bounds =
+                    OffscreenRenderer::locate(engine, size, focus.clone(), &Palette::default().coloring(1.0)).await;
+                locating.set_value(false);
+                let Some(bounds) = bounds else {
+                    return;
+                };
+                keyframes.update(|keyframes| {
+                    let focus = focus.to_string();
+                    if let Some(keyframe) = keyframes
+                        .iter_mut()
+                        .find(|keyframe| keyframe.bounds.is_none() && keyframe.focus.to_string() == focus)
+                    {
+                        keyframe.bounds = Some(bounds);
+                    }
+                });
+            });
+        }
+    });
+
+    let play = {
+        let resume = animation.resume.clone();
+        move |_| {
+            if playing.get_untracked() {
+                playing.set(false);
+            } else if let Some(resolved) = Path::resolve(keyframes.get_untracked(), active_palette.get_untracked()) {
+                path.set_value(Some(resolved));
+                elapsed.set_value(0.0);
+                shown_palette.set_value(None);
+                playing.set(true);
+                resume();
+            }
+        }
+    };
+
+    let export = move |_| {
+        let Some(path) = Path::resolve(keyframes.get_untracked(), active_palette.get_untracked()) else {
+            return;
+        };
+        let (width, height, fps) = (width.get_untracked(), height.get_untracked(), fps.get_untracked());
+        let name = sequence_name.get_untracked().trim().to_string();
+        let name = if name.is_empty() {
+            "flythrough".to_string()
+        } else {
+            name
+        };
+        let engine = context.state.render_settings().engine().get_untracked();
+        cancelled.set(false);
+        let frames = (path.duration() * fps as f64).ceil() as usize + 1;
+        too_large.set(frames > MAX_FILES);
+        if frames > MAX_FILES {
+            return;
+        }
+        spawn_local(async move {
+            // one offscreen engine renders all frames
+            let (bounds, palette) = path.at(0.0);
+            let Some(renderer) =
+                OffscreenRenderer::acquire(engine, TILE_SIZE, &palette.coloring(magnification(&bounds)))
+            else {
+                return;
+            };
+            // every frame is handed over to the browser once rendered, only the central directory is kept
+            let mut archive = ZipWriter::default();
+            let parts = js_sys::Array::new();
+            for frame in 0..frames {
+                let (bounds, palette) = path.at(frame as f64 / fps as f64);
+                renderer.set_coloring(&palette.coloring(magnification(&bounds)));
+                let png = render_png(
+                    &renderer,
+                    &Viewport::new(&bounds, width, height),
+                    width,
+                    height,
+                    |fraction| {
+                        progress.set(Some((frame as f64 + fraction) / frames as f64));
+                        !cancelled.get_untracked()
+                    },
+                )
+                .await;
+                let Some(png) = png else {
+                    break;
+                };
+                match archive.add(&format!("{}/{:04}.png", name, frame), &png) {
+                    Ok(part) => {
+                        parts.push(&js_sys::Uint8Array::from(part.as_slice()));
+                    }
+                    Err(_) => {
+                        too_large.set(true);
+                        break;
+                    }
+                }
+            }
+            progress.set(None);
+            if parts.length() as usize == frames {
+                parts.push(&js_sys::Uint8Array::from(archive.finish().as_slice()));
+                download_parts(&format!("{}.zip", name), "application/zip", &parts);
+            }
+        });
+    };
+
+    let update_keyframe = move |index: usize, update: &dyn Fn(&mut Keyframe)| {
+        keyframes.update(|keyframes| {
+            if let Some(keyframe) = keyframes.get_mut(index) {
+                update(keyframe);
+            }
+        })
+    };
+
+    view! {
+        <div class="px-4 py-2 bg-gray-100 shadow-sm space-y-2">
+            <div class="flex flex-row flex-wrap items-center gap-2">
+                <select
+                    on:change=move |ev| location_id.set(event_target_value(&ev).parse().ok())
+                    prop:value=move || location_id.get().map_or(String::new(), |id| id.to_string())
+                    class="px-2 py-1 rounded-md border border-gray-300 text-sm"
+                >
+                    <option value="">"Choose a saved location"</option>
+                    {move || {
+                        let mut names = locations.with(|locations| {
+                            locations.iter().map(|location| (location.name.clone(), location.id)).collect::<Vec<_>>()
+                        });
+                        names.sort();
+                        names.into_iter().map(|(name, id)| view! { <option value=id.to_string()>{name}</option> }).collect_view()
+                    }}
+                </select>
+                <button
+                    on:click=move |_| {
+                        let location = location_id.get_untracked().and_then(|id| {
+                            locations.with_untracked(|locations| locations.iter().find(|location| location.id == id).cloned())
+                        });
+                        if let Some(location) = location {
+                            keyframes.update(|keyframes| keyframes.push(Keyframe {
+                                name: location.name,
+                                focus: location.focus,
+                                palette: location.palette,
+                                duration: 5.0,
+                                easing: Easing::default(),
+                                bounds: None,
+                            }));
+                        }
+                    }
+                    class="px-3 py-1 bg-green-600 hover:bg-green-500 rounded-md text-white text-sm font-medium transition"
+                >
+                    "Add keyframe"
+                </button>
+            </div>
+
+            <div class="space-y-1">
+                {move || keyframes.get().into_iter().enumerate().map(|(index, keyframe)| view! {
+                    <div class="flex flex-row flex-wrap items-center gap-2 bg-gray-800 text-white rounded px-3 py-2">
+                        <span class="text-xs font-mono text-gray-400">{index + 1}</span>
+                        <span class="flex-1 text-sm font-semibold">{keyframe.name.clone()}</span>
+                        <Show when=move || index != 0>
+                            <label class="flex items-center gap-1 text-xs text-gray-300">
+                                <input
+                                    type="number"
+                                    min="0"
+                                    step="0.5"
+                                    prop:value=keyframe.duration.to_string()
+                                    on:change=move |ev| {
+                                        if let Ok(duration) = event_target_value(&ev).parse::<f64>() {
+                                            update_keyframe(index, &|keyframe| keyframe.duration = duration.max(0.0));
+                                        }
+                                    }
+                                    class="w-16 px-1 rounded text-gray-900"
+                                />
+                                "s"
+                            </label>
+                            <select
+                                on:change=move |ev| {
+                                    let label = event_target_value(&ev);
+                                    if let Some(easing) = Easing::ALL.into_iter().find(|easing| easing.label() == label) {
+                                        update_keyframe(index, &|keyframe| keyframe.easing = easing);
+                                    }
+                                }
+                                class="px-1 rounded text-xs text-gray-900"
+                            >
+                                {Easing::ALL.into_iter().map(|easing| view! {
+                                    <option value=easing.label() selected=easing == keyframe.easing>{easing.label()}</option>
+                                }).collect_view()}
+                            </select>
+                        </Show>
+                        <button
+                            on:click=move |_| keyframes.update(|keyframes| if index > 0 { keyframes.swap(index - 1, index) })
+                            class="px-2 text-gray-300 hover:text-white"
+                            title="Move up"
+                        >
+                            "↑"
+                        </button>
+                        <button
+                            on:click=move |_| keyframes.update(|keyframes| if index + 1 < keyframes.len() { keyframes.swap(index, index + 1) })
+                            class="px-2 text-gray-300 hover:text-white"
+                            title="Move down"
+                        >
+                            "↓"
+                        </button>
+                        <button
+                            on:click=move |_| keyframes.update(|keyframes| { keyframes.remove(index); })
+                            class="px-2 text-red-400 hover:text-red-300"
+                            title="Remove"
+                        >
+                            "✕"
+                        </button>
+                    </div>
+                }).collect_view()}
+                <Show when=move || keyframes.with(|keyframes| keyframes.len() < 2)>
+                    <p class="text-sm text-gray-500">"Add at least two keyframes to fly between them"</p>
+                </Show>
+            </div>
+
+            <div class="flex flex-row flex-wrap items-center gap-2">
+                <button
+                    on:click=play
+                    disabled=move || keyframes.with(|keyframes| keyframes.len() < 2) || !located.get() || progress.get().is_some()
+                    class="px-3 py-1 bg-blue-600 hover:bg-blue-500 disabled:opacity-50 rounded-md text-white text-sm font-medium transition"
+                >
+                    {move || if playing.get() { "Stop" } else { "Play" }}
+                </button>
+                <input
+                    type="text"
+                    placeholder="Sequence name"
+                    prop:value=move || sequence_name.get()
+                    on:input=move |ev| sequence_name.set(event_target_value(&ev))
+                    class="px-2 py-1 rounded-md border border-gray-300 text-sm"
+                />
+                <button
+                    on:click=move |_| {
+                        let name = sequence_name.get_untracked().trim().to_string();
+                        if !name.is_empty() {
+                            let sequence = Sequence { name: name.clone(), keyframes: keyframes.get_untracked() };
+                            sequences.update(|sequences| match sequences.iter_mut().find(|sequence| sequence.name == name) {
+                                Some(saved) => *saved = sequence,
+                                None => sequences.push(sequence),
+                            });
+                        }
+                    }
+                    class="px-3 py-1 bg-green-600 hover:bg-green-500 rounded-md text-white text-sm font-medium transition"
+                >
+                    "Save"
+                </button>
+            </div>
+
+            <For
+                each=move || sequences.get()
+                key=|sequence| sequence.name.clone()
+                let(sequence)
+            >
+                <div class="flex flex-row items-center justify-between gap-2 bg-gray-800 text-white rounded px-3 py-2">
+                    <span class="text-sm font-semibold">{sequence.name.clone()}</span>
+                    <span class="text-xs text-gray-400">{format!("{} keyframes", sequence.keyframes.len())}</span>
+                    <div class="flex gap-2">
+                        <button
+                            on:click={
+                                let sequence = sequence.clone();
+                                move |_| {
+                                    sequence_name.set(sequence.name.clone());
+                                    keyframes.set(sequence.keyframes.clone());
+                                }
+                            }
+                            class="px-3 py-1 bg-blue-600 hover:bg-blue-500 rounded-md text-white text-sm font-medium transition"
+                        >
+                            "Load"
+                        </button>
+                        <button
+                            on:click=move |_| sequences.update(|sequences| sequences.retain(|saved| saved.name != sequence.name))
+                            class="px-3 py-1 bg-red-600 hover:bg-red-500 rounded-md text-white text-sm font-medium transition"
+                        >
+                            "Delete"
+                        </button>
+                    </div>
+                </div>
+            </For>
+
+            <div class="flex flex-row flex-wrap items-center gap-2">
+                <label class="flex items-center gap-1 text-sm text-gray-700">
+                    "Frames"
+                    <input
+                        type="number"
+                        min="1"
+                        prop:value=move || width.get().to_string()
+                        on:change=move |ev| if let Ok(value) = event_target_value(&ev).parse() { width.set(value) }
+                        class="w-20 px-2 py-1 rounded-md border border-gray-300"
+                    />
+                    "×"
+                    <input
+                        type="number"
+                        min="1"
+                        prop:value=move || height.get().to_string()
+                        on:change=move |ev| if let Ok(value) = event_target_value(&ev).parse() { height.set(value) }
+                        class="w-20 px-2 py-1 rounded-md border border-gray-300"
+                    />
+                    "at"
+                    <input
+                        type="number"
+                        min="1"
+                        prop:value=move || fps.get().to_string()
+                        on:change=move |ev| if let Ok(value) = event_target_value(&ev).parse::<u32>() { fps.set(value.max(1)) }
+                        class="w-16 px-2 py-1 rounded-md border border-gray-300"
+                    />
+                    "fps"
+                </label>
+                <Show
+                    when=move || progress.get().is_some()
+                    fallback=move || view! {
+                        <button
+                            on:click=export.clone()
+                            disabled=move || {
+                                playing.get()
+                                    || !located.get()
+                                    || keyframes.with(|keyframes| keyframes.len() < 2)
+                                    || !is_supported_size(width.get(), height.get())
+                            }
+                            class="px-3 py-1 bg-gray-700 hover:bg-gray-600 disabled:opacity-50 rounded-md text-white text-sm font-medium transition"
+                        >
+                            "Export frames"
+                        </button>
+                    }
+                >
+                    <progress max="1" value=move || progress.get().unwrap_or_default() class="w-40"/>
+                    <button
+                        on:click=move |_| cancelled.set(true)
+                        class="px-3 py-1 bg-red-600 hover:bg-red-500 rounded-md text-white text-sm font-medium transition"
+                    >
+                        "Cancel"
+                    </button>
+                </Show>
+            </div>
+            <p class="text-xs text-gray-500">"Frames are downloaded as numbered PNG images in a single .zip archive"</p>
+            <Show when=move || too_large.get()>
+                <p class="text-sm text-red-500">"These frames don't fit into a .zip archive, lower the size or frame rate"</p>
+            </Show>
+        </div>
+    }
+}
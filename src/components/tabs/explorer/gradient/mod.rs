@@ -1,2 +1,33 @@
 pub mod step;
 pub mod wave;
+
+use leptos::prelude::*;
+use reactive_stores::Store;
+
+use crate::color::Gradient;
+
+/// Read-only bar showing a gradient, with `children` laid over it.
+#[component]
+pub fn Preview(gradient: Gradient, length: f64, offset: f64, children: Children) -> impl IntoView {
+    match gradient {
+        Gradient::Wave(gradient) => view! {
+            <wave::Bar gradient length offset>
+                {children()}
+            </wave::Bar>
+        }
+        .into_any(),
+        Gradient::Step(gradient) => view! {
+            <step::Bar
+                position=RwSignal::new(0.0).write_only()
+                width=RwSignal::new(0.0).write_only()
+                points=Store::new(gradient.into())
+                on_click=|_| {}
+                length
+                offset
+            >
+                {children()}
+            </step::Bar>
+        }
+        .into_any(),
+    }
+}
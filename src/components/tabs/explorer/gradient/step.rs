@@ -4,47 +4,128 @@ use leptos_use::{use_draggable, use_element_bounding};
 use reactive_stores::{AtKeyed, Store};
 use wasm_bindgen::JsCast;
 
-use crate::color::{Checkpoint, StepGradient, hex_to_rgb, smoothstep};
+use crate::color::{Checkpoint, Interpolation, StepGradient, hex_to_rgb};
+
+// position change of one arrow key press, and of one with Shift held
+const NUDGE_STEP: f64 = 0.01;
+const FINE_NUDGE_STEP: f64 = 0.001;
+// edits kept for undo
+const HISTORY_LIMIT: usize = 100;
 
 #[derive(Store, Default, Clone)]
 pub struct Points {
-    #[store(key: u8 = |checkpoint| checkpoint.id)]
+    #[store(key: u32 = |checkpoint| checkpoint.id)]
     checkpoints: Vec<IndexedCheckpoint>,
-    next_id: u8,
+    next_id: u32,
+    interpolation: Interpolation,
 }
 
 impl Points {
-    fn add_checkpoint(&mut self, checkpoint: Checkpoint) -> u8 {
+    fn add_checkpoint(&mut self, checkpoint: Checkpoint) -> u32 {
+        // ids are never reused while their checkpoint exists, even once the counter wraps around
+        while self.checkpoints.iter().any(|checkpoint| checkpoint.id == self.next_id) {
+            self.next_id = self.next_id.wrapping_add(1);
+        }
         let id = self.next_id;
         self.checkpoints.push(IndexedCheckpoint { id, inner: checkpoint });
-        self.next_id += 1;
+        self.next_id = self.next_id.wrapping_add(1);
         id
     }
 
+    fn checkpoint_mut(&mut self, id: u32) -> Option<&mut Checkpoint> {
+        self.checkpoints
+            .iter_mut()
+            .find(|checkpoint| checkpoint.id == id)
+            .map(|checkpoint| &mut checkpoint.inner)
+    }
+
+    /// Spread the checkpoints evenly over the bar, keeping their order.
+    fn distribute(&mut self) {
+        let count = self.checkpoints.len();
+        if count < 2 {
+            return;
+        }
+        self.checkpoints
+            .sort_by(|a, b| a.inner.position.total_cmp(&b.inner.position));
+        for (index, checkpoint) in self.checkpoints.iter_mut().enumerate() {
+            checkpoint.inner.position = index as f64 / (count - 1) as f64;
+        }
+    }
+
+    fn reverse(&mut self) {
+        for checkpoint in &mut self.checkpoints {
+            checkpoint.inner.position = 1.0 - checkpoint.inner.position;
+        }
+    }
+
+    /// Squeeze the gradient into the first half of the bar and repeat it backwards in the second half.
+    fn mirror(&mut self) {
+        let mirrored = self
+            .checkpoints
+            .iter_mut()
+            .map(|checkpoint| {
+                checkpoint.inner.position /= 2.0;
+                Checkpoint {
+                    position: 1.0 - checkpoint.inner.position,
+                    ..checkpoint.inner
+                }
+            })
+            .collect::<Vec<_>>();
+        for checkpoint in mirrored {
+            self.add_checkpoint(checkpoint);
+        }
+    }
+
+    /// Copy a checkpoint halfway to the next one, or to the previous one for the last checkpoint.
+    fn duplicate(&mut self, id: u32) -> Option<u32> {
+        let checkpoint = self.checkpoints.iter().find(|checkpoint| checkpoint.id == id)?.inner;
+        let next = self
+            .checkpoints
+            .iter()
+            .map(|other| other.inner.position)
+            .filter(|position| *position > checkpoint.position)
+            .min_by(f64::total_cmp);
+        let previous = self
+            .checkpoints
+            .iter()
+            .filter(|other| other.id != id)
+            .map(|other| other.inner.position)
+            .filter(|position| *position <= checkpoint.position)
+            .max_by(f64::total_cmp);
+        let position = match (next, previous) {
+            (Some(next), _) => (checkpoint.position + next) / 2.0,
+            (None, Some(previous)) => (checkpoint.position + previous) / 2.0,
+            (None, None) => (checkpoint.position + 0.5) % 1.0,
+        };
+        Some(self.add_checkpoint(Checkpoint { position, ..checkpoint }))
+    }
+
     fn export(&self) -> StepGradient {
-        if self.checkpoints.is_empty() {
-            StepGradient {
-                checkpoints: vec![Checkpoint {
-                    position: 0.0,
-                    color: [255, 255, 255],
-                }],
-            }
+        let checkpoints = if self.checkpoints.is_empty() {
+            vec![Checkpoint {
+                position: 0.0,
+                color: [255, 255, 255],
+            }]
         } else {
-            StepGradient {
-                checkpoints: self
-                    .checkpoints
-                    .iter()
-                    .sorted_by(|a, b| a.inner.position.partial_cmp(&b.inner.position).unwrap())
-                    .map(|ic| ic.inner)
-                    .collect(),
-            }
+            self.checkpoints
+                .iter()
+                .sorted_by(|a, b| a.inner.position.partial_cmp(&b.inner.position).unwrap())
+                .map(|ic| ic.inner)
+                .collect()
+        };
+        StepGradient {
+            checkpoints,
+            interpolation: self.interpolation,
         }
     }
 }
 
 impl From<StepGradient> for Points {
     fn from(value: StepGradient) -> Self {
-        let mut this = Self::default();
+        let mut this = Self {
+            interpolation: value.interpolation,
+            ..Self::default()
+        };
         for checkpoint in value.checkpoints {
             this.add_checkpoint(checkpoint);
         }
@@ -54,20 +135,36 @@ impl From<StepGradient> for Points {
 
 #[derive(Clone, PartialEq, Store)]
 struct IndexedCheckpoint {
-    id: u8,
+    id: u32,
     inner: Checkpoint,
 }
 
+/// Earlier and undone states of the editor.
+#[derive(Clone, Default)]
+struct History {
+    undo: Vec<Points>,
+    redo: Vec<Points>,
+}
+
 #[component]
 fn DraggableArrow(
-    checkpoint: AtKeyed<Store<Points>, Points, u8, Vec<IndexedCheckpoint>>,
-    active_checkpoint_id: RwSignal<Option<u8>>,
+    checkpoint: AtKeyed<Store<Points>, Points, u32, Vec<IndexedCheckpoint>>,
+    active_checkpoint_id: RwSignal<Option<u32>>,
     #[prop(into)] bar_width: Signal<f64>,
     #[prop(into)] bar_left: Signal<f64>,
+    on_drag_start: impl Fn() + 'static,
 ) -> impl IntoView {
     let el = NodeRef::<leptos::html::Div>::new();
     let draggable = use_draggable(el);
 
+    Effect::new(move |was_dragging: Option<bool>| {
+        let is_dragging = draggable.is_dragging.get();
+        if is_dragging && was_dragging == Some(false) {
+            on_drag_start();
+        }
+        is_dragging
+    });
+
     Effect::new(move || {
         if draggable.is_dragging.get() {
             let x = draggable.x.get();
@@ -130,38 +227,6 @@ pub fn Bar(
         on_click(position)
     };
 
-    let step_gradient = move |t: f64| {
-        let points = points.read();
-        let checkpoints = points
-            .checkpoints
-            .iter()
-            .map(|checkpoint| checkpoint.inner)
-            .sorted_by(|a, b| a.position.partial_cmp(&b.position).unwrap())
-            .collect::<Vec<_>>();
-
-        if checkpoints.is_empty() {
-            return [255, 255, 255];
-        }
-
-        let (a, b, local_t) = checkpoints
-            .windows(2)
-            .find(|&pair| {
-                let [a, b] = *pair else { unreachable!() };
-                t >= a.position && t < b.position
-            })
-            .map(|pair| {
-                let [a, b] = *pair else { unreachable!() };
-                (a, b, (t - a.position) / (b.position - a.position))
-            })
-            .unwrap_or_else(|| {
-                let first = checkpoints[0];
-                let last = *checkpoints.last().unwrap();
-                let range = 1.0 - last.position + first.position;
-                (last, first, ((t - last.position + 1.0) % 1.0) / range)
-            });
-        smoothstep(&a.color, &b.color, local_t)
-    };
-
     Effect::new(move || {
         if let Some(canvas) = node_ref.get() {
             let width = bounding.width.get() as u32;
@@ -177,6 +242,7 @@ pub fn Bar(
             canvas.set_width(width);
             canvas.set_height(height);
 
+            let gradient = points.read().export();
             for x in 0..width {
                 let t = if let (Some(mut length), Some(offset)) = (length, offset) {
                     length = (length * 8.0).min(width as f64);
@@ -184,7 +250,7 @@ pub fn Bar(
                 } else {
                     x as f64 / width as f64
                 };
-                let [r, g, b] = step_gradient(t);
+                let [r, g, b] = gradient.color_at(t);
                 ctx.set_fill_style_str(&format!("rgb({},{},{})", r, g, b));
                 ctx.fill_rect(x as f64, 0.0, 1.0, height as f64);
             }
@@ -207,16 +273,74 @@ pub fn Bar(
     }
 }
 
+#[component]
+fn ToolButton(label: &'static str, title: &'static str, on_click: impl Fn() + 'static) -> impl IntoView {
+    view! {
+        <button
+            on:click=move |_| on_click()
+            title=title
+            class="px-2 py-1 bg-gray-700 hover:bg-gray-600 disabled:opacity-50 text-white rounded text-xs font-medium transition"
+        >
+            {label}
+        </button>
+    }
+}
+
 #[component]
 pub fn Editor(gradient: StepGradient, on_update: impl Fn(StepGradient) + 'static) -> impl IntoView {
     let bar_width = RwSignal::new(0.0);
     let bar_left = RwSignal::new(0.0);
     let points = Store::new(Points::from(gradient));
     let active_checkpoint_id = RwSignal::new(Some(0));
+    let history = RwSignal::new(History::default());
 
     Effect::new(move || on_update(points.get().export()));
 
+    // remember the current state before an edit
+    let record = move || {
+        history.update(|history| {
+            history.undo.push(points.get_untracked());
+            if history.undo.len() > HISTORY_LIMIT {
+                history.undo.remove(0);
+            }
+            history.redo.clear();
+        });
+    };
+
+    let restore = move |state: Points| {
+        points.set(state);
+        points.checkpoints().update_keys();
+        let active_exists = active_checkpoint_id.get_untracked().is_some_and(|id| {
+            points.with_untracked(|points| points.checkpoints.iter().any(|checkpoint| checkpoint.id == id))
+        });
+        if !active_exists {
+            active_checkpoint_id.set(None);
+        }
+    };
+
+    let undo = move || {
+        if let Some(previous) = history.try_update(|history| history.undo.pop()).flatten() {
+            history.update(|history| history.redo.push(points.get_untracked()));
+            restore(previous);
+        }
+    };
+
+    let redo = move || {
+        if let Some(next) = history.try_update(|history| history.redo.pop()).flatten() {
+            history.update(|history| history.undo.push(points.get_untracked()));
+            restore(next);
+        }
+    };
+
+    // apply an edit of the whole gradient as one undoable step
+    let edit = move |edit: &dyn Fn(&mut Points)| {
+        record();
+        points.update(|points| edit(points));
+        points.checkpoints().update_keys();
+    };
+
     let on_gradient_click = move |position| {
+        record();
         let mut checkpoint_id = 0;
         points.update(|points| {
             checkpoint_id = points.add_checkpoint(Checkpoint {
@@ -227,8 +351,68 @@ pub fn Editor(gradient: StepGradient, on_update: impl Fn(StepGradient) + 'static
         active_checkpoint_id.set(Some(checkpoint_id));
     };
 
+    let nudge = move |id: u32, delta: f64| {
+        record();
+        points.update(|points| {
+            if let Some(checkpoint) = points.checkpoint_mut(id) {
+                checkpoint.position = (checkpoint.position + delta).clamp(0.0, 1.0);
+            }
+        });
+    };
+
+    let delete = move |id: u32| {
+        record();
+        points.update(|points| {
+            points.checkpoints.retain(|checkpoint| checkpoint.id != id);
+        });
+        points.checkpoints().update_keys();
+        active_checkpoint_id.set(None);
+    };
+
+    // keys pressed while the editor has focus, form fields inside it keep theirs
+    let on_keydown = move |ev: web_sys::KeyboardEvent| {
+        if ev.target() != ev.current_target() {
+            return;
+        }
+        let command = ev.ctrl_key() || ev.meta_key();
+        let handled = match (ev.key().as_str(), active_checkpoint_id.get_untracked()) {
+            ("z" | "Z", _) if command && ev.shift_key() => {
+                redo();
+                true
+            }
+            ("z", _) if command => {
+                undo();
+                true
+            }
+            ("y", _) if command => {
+                redo();
+                true
+            }
+            ("ArrowLeft" | "ArrowRight", Some(id)) => {
+                let step = if ev.shift_key() { FINE_NUDGE_STEP } else { NUDGE_STEP };
+                nudge(id, if ev.key() == "ArrowLeft" { -step } else { step });
+                true
+            }
+            ("Delete" | "Backspace", Some(id)) => {
+                delete(id);
+                true
+            }
+            // kept from the explorer shortcuts even without a checkpoint, where Backspace goes to the parent NFT
+            ("Delete" | "Backspace", None) => true,
+            _ => false,
+        };
+        if handled {
+            ev.prevent_default();
+            ev.stop_propagation();
+        }
+    };
+
     view! {
-        <div class="relative w-full">
+        <div
+            tabindex="0"
+            on:keydown=on_keydown
+            class="relative w-full outline-none focus-visible:ring-2 focus-visible:ring-blue-400 rounded"
+        >
             <Bar
                 position=bar_left.write_only()
                 width=bar_width.write_only()
@@ -236,6 +420,54 @@ pub fn Editor(gradient: StepGradient, on_update: impl Fn(StepGradient) + 'static
                 on_click=on_gradient_click
             />
 
+            <div class="flex items-center gap-2 mt-2">
+                <label class="text-sm font-medium">"Interpolation:"</label>
+                <select
+                    on:change=move |ev| {
+                        if let Some(interpolation) = event_target_value(&ev)
+                            .parse::<usize>()
+                            .ok()
+                            .and_then(|index| Interpolation::ALL.get(index))
+                        {
+                            record();
+                            points.interpolation().set(*interpolation);
+                        }
+                    }
+                    class="px-2 py-1 rounded-md border border-gray-300 text-sm text-gray-900"
+                >
+                    {Interpolation::ALL.into_iter().enumerate().map(|(index, interpolation)| view! {
+                        <option
+                            value=index.to_string()
+                            selected=move || points.interpolation().get() == interpolation
+                        >
+                            {interpolation.label()}
+                        </option>
+                    }).collect_view()}
+                </select>
+            </div>
+
+            <div class="flex flex-wrap items-center gap-1 mt-2">
+                <ToolButton label="Distribute" title="Space the checkpoints evenly" on_click=move || edit(&Points::distribute)/>
+                <ToolButton label="Reverse" title="Flip the gradient" on_click=move || edit(&Points::reverse)/>
+                <ToolButton label="Mirror" title="Repeat the gradient backwards in the second half" on_click=move || edit(&Points::mirror)/>
+                <button
+                    on:click=move |_| undo()
+                    disabled=move || history.with(|history| history.undo.is_empty())
+                    title="Undo (Ctrl+Z)"
+                    class="px-2 py-1 bg-gray-700 hover:bg-gray-600 disabled:opacity-50 text-white rounded text-xs font-medium transition"
+                >
+                    "Undo"
+                </button>
+                <button
+                    on:click=move |_| redo()
+                    disabled=move || history.with(|history| history.redo.is_empty())
+                    title="Redo (Ctrl+Shift+Z)"
+                    class="px-2 py-1 bg-gray-700 hover:bg-gray-600 disabled:opacity-50 text-white rounded text-xs font-medium transition"
+                >
+                    "Redo"
+                </button>
+            </div>
+
             <For
                 each=move || points.checkpoints()
                 key=|checkpoint| checkpoint.read().id
@@ -246,6 +478,7 @@ pub fn Editor(gradient: StepGradient, on_update: impl Fn(StepGradient) + 'static
                     active_checkpoint_id
                     bar_width=bar_width
                     bar_left=bar_left
+                    on_drag_start=record
                 />
             </For>
 
@@ -254,28 +487,58 @@ pub fn Editor(gradient: StepGradient, on_update: impl Fn(StepGradient) + 'static
                 active_checkpoint_id.get().map(|id| {
                     let active_checkpoint = AtKeyed::new(points.checkpoints(), id);
                     view! {
-                        <div class="mt-4 flex items-center space-x-4">
+                        <div class="mt-4 flex flex-wrap items-center gap-4">
                             <div class="flex items-center space-x-2">
                                 <label class="text-sm font-medium">"Selected Color:"</label>
                                 <input
                                     type="color"
                                     class="w-8 h-8 rounded border shadow"
                                     prop:value=active_checkpoint.read().inner.color_hex()
+                                    on:focus=move |_| record()
                                     on:input=move |ev| {
                                         let new_color = hex_to_rgb(&event_target_value(&ev)).unwrap();
                                         active_checkpoint.inner().update(|checkpoint| checkpoint.color = new_color);
                                     }
                                 />
                             </div>
+                            <div class="flex items-center space-x-2">
+                                <label class="text-sm font-medium">"Position:"</label>
+                                <input
+                                    type="number"
+                                    min="0"
+                                    max="100"
+                                    step="0.1"
+                                    prop:value=move || format!("{:.1}", active_checkpoint.inner().get().position * 100.0)
+                                    on:change=move |ev| {
+                                        if let Ok(percent) = event_target_value(&ev).parse::<f64>() {
+                                            if percent.is_finite() {
+                                                record();
+                                                active_checkpoint
+                                                    .inner()
+                                                    .update(|checkpoint| checkpoint.position = (percent / 100.0).clamp(0.0, 1.0));
+                                            }
+                                        }
+                                    }
+                                    class="w-20 px-2 py-1 rounded-md border border-gray-300 text-sm text-gray-900"
+                                />
+                                <span class="text-sm">"%"</span>
+                            </div>
                             <button
-                                class="px-4 py-1 bg-red-500 text-white rounded hover:bg-red-600"
+                                class="px-4 py-1 bg-gray-700 text-white rounded hover:bg-gray-600"
                                 on:click=move |_| {
-                                    points.update(|points| {
-                                        points.checkpoints.retain(|checkpoint| checkpoint.id != id);
-                                    });
-                                    points.checkpoints().update_keys();
-                                    active_checkpoint_id.set(None);
+                                    record();
+                                    let mut duplicate = None;
+                                    points.update(|points| duplicate = points.duplicate(id));
+                                    if duplicate.is_some() {
+                                        active_checkpoint_id.set(duplicate);
+                                    }
                                 }
+                            >
+                                "Duplicate"
+                            </button>
+                            <button
+                                class="px-4 py-1 bg-red-500 text-white rounded hover:bg-red-600"
+                                on:click=move |_| delete(id)
                             >
                                 "Delete Checkpoint"
                             </button>
@@ -283,6 +546,9 @@ pub fn Editor(gradient: StepGradient, on_update: impl Fn(StepGradient) + 'static
                     }
                 })
             }}
+            <p class="mt-1 text-xs text-gray-500">
+                "Click an arrow, then use ←/→ to nudge it (Shift for finer steps) and Delete to remove it"
+            </p>
         </div>
     }
 }
@@ -1,44 +1,20 @@
 use leptos::prelude::*;
 use leptos_use::use_element_size;
+use reactive_stores::{Store, Subfield};
 use wasm_bindgen::JsCast;
 
-#[derive(Clone)]
-struct Wave {
-    bias: f32,
-    amplitude: f32,
-    frequency: f32,
-    phase: f32,
-}
-
-impl Wave {
-    fn new(bias: f32, amplitude: f32, frequency: f32, phase: f32) -> Self {
-        Self {
-            bias,
-            amplitude,
-            frequency,
-            phase,
-        }
-    }
-}
-
-fn gradient_wave(t: f32, dx: f32, dy: f32, waves: [Wave; 3]) -> [u8; 3] {
-    let t = (1.0 - t * 2.0).abs();
-    itertools::izip!(
-        waves,
-        [dx.sin(), (dy - dx).sin(), (3.14 * dx).cos()],
-        [f32::cos, f32::sin, f32::cos]
-    )
-    .map(|(wave, variation, trig)| {
-        let value = wave.bias + wave.amplitude * trig(t * wave.frequency + wave.phase + variation);
-        (value.clamp(0.0, 1.0) * 255.0) as u8
-    })
-    .collect::<Vec<_>>()
-    .try_into()
-    .unwrap()
-}
+use crate::{
+    color::{Wave, WaveGradient, WaveGradientStoreFields, WaveStoreFields},
+    components::primitive::Slider,
+};
 
 #[component]
-pub fn Wave() -> impl IntoView {
+pub fn Bar(
+    #[prop(into)] gradient: Signal<WaveGradient>,
+    #[prop(optional)] length: Option<f64>,
+    #[prop(optional)] offset: Option<f64>,
+    #[prop(optional)] children: Option<Children>,
+) -> impl IntoView {
     let canvas_ref = NodeRef::<leptos::html::Canvas>::new();
     let canvas_size = use_element_size(canvas_ref);
 
@@ -57,15 +33,15 @@ pub fn Wave() -> impl IntoView {
             canvas.set_width(width);
             canvas.set_height(height);
 
-            let waves = [
-                Wave::new(1.0, 0.5, 6.28, 0.9),
-                Wave::new(1.0, 0.5, 5.88, -3.14),
-                Wave::new(1.0, 0.5, 3.14, -3.64),
-            ];
-
+            let gradient = gradient.get();
             for x in 0..width {
-                let t: f32 = x as f32 / width as f32;
-                let [r, g, b] = gradient_wave(t, 0.0, 0.0, waves.clone());
+                let t = if let (Some(mut length), Some(offset)) = (length, offset) {
+                    length = (length * 8.0).min(width as f64);
+                    (x as f64 + length * offset) % length / length
+                } else {
+                    x as f64 / width as f64
+                };
+                let [r, g, b] = gradient.color_at(t);
                 ctx.set_fill_style_str(&format!("rgb({},{},{})", r, g, b));
                 ctx.fill_rect(x as f64, 0.0, 1.0, height as f64);
             }
@@ -73,6 +49,59 @@ pub fn Wave() -> impl IntoView {
     });
 
     view! {
-        <canvas node_ref=canvas_ref class="h-10 w-full rounded"/>
+        <div class="relative h-12 w-full">
+            <canvas node_ref=canvas_ref class="absolute inset-0 h-full w-full rounded z-0"/>
+            {children.map(|children| view! {
+                <div class="absolute inset-0 z-10 flex flex-col justify-center items-center">
+                    {children()}
+                </div>
+            })}
+        </div>
+    }
+}
+
+type WaveField = Subfield<Store<WaveGradient>, WaveGradient, Wave>;
+
+#[component]
+fn Parameter(label: &'static str, min: f64, max: f64, value: Subfield<WaveField, Wave, f64>) -> impl IntoView {
+    view! {
+        <div class="space-y-1">
+            <div class="flex justify-between items-center">
+                <label class="text-xs text-gray-300">{label}</label>
+                <span class="text-xs font-mono text-accent2">{move || format!("{:.3}", value.get())}</span>
+            </div>
+            <Slider min max value class="w-full bg-gray-300 rounded-full focus:outline-none"/>
+        </div>
+    }
+}
+
+#[component]
+fn Channel(name: &'static str, wave: WaveField) -> impl IntoView {
+    view! {
+        <div class="space-y-2">
+            <p class="text-sm font-medium text-gray-300">{name}</p>
+            <Parameter label="Bias" min=-1.0 max=2.0 value=wave.bias()/>
+            <Parameter label="Amplitude" min=-1.0 max=1.0 value=wave.amplitude()/>
+            <Parameter label="Frequency" min=0.0 max=20.0 value=wave.frequency()/>
+            <Parameter label="Phase" min=-std::f64::consts::PI max=std::f64::consts::PI value=wave.phase()/>
+        </div>
+    }
+}
+
+#[component]
+pub fn Editor(gradient: WaveGradient, on_update: impl Fn(WaveGradient) + 'static) -> impl IntoView {
+    let gradient = Store::new(gradient);
+
+    Effect::new(move || on_update(gradient.get()));
+
+    view! {
+        <div class="w-full space-y-4">
+            <Bar gradient=Signal::derive(move || gradient.get())/>
+            <div class="grid grid-cols-3 gap-4">
+                <Channel name="Red" wave=gradient.red()/>
+                <Channel name="Green" wave=gradient.green()/>
+                <Channel name="Blue" wave=gradient.blue()/>
+            </div>
+        </div>
     }
 }
@@ -0,0 +1,382 @@
+use std::collections::BTreeSet;
+
+use leptos::{prelude::*, task::spawn_local};
+use reactive_stores::Store;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    color::Gradient,
+    palette_file::Format,
+    util::{download_file, format_timestamp, load_item, read_file_text},
+};
+
+use super::{gradient::Preview, visuals::Palette};
+
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
+pub struct SavedPalette {
+    pub id: u64,
+    pub name: String,
+    pub created: f64,
+    pub tags: Vec<String>,
+    pub favourite: bool,
+    pub palette: Palette,
+}
+
+/// Saved palettes, taking over the unnamed palettes stored before the library existed.
+pub fn load() -> Vec<SavedPalette> {
+    load_item::<Vec<SavedPalette>>("palette_library").unwrap_or_else(|| {
+        let created = js_sys::Date::now();
+        load_item::<Vec<Palette>>("palettes")
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .map(|(index, palette)| SavedPalette {
+                id: index as u64,
+                name: format!("Palette {}", index + 1),
+                created,
+                tags: Vec::new(),
+                favourite: false,
+                palette,
+            })
+            .collect()
+    })
+}
+
+pub fn add(palettes: &mut Vec<SavedPalette>, name: String, palette: Palette) {
+    palettes.push(SavedPalette {
+        id: palettes.iter().map(|saved| saved.id + 1).max().unwrap_or(0),
+        name,
+        created: js_sys::Date::now(),
+        tags: Vec::new(),
+        favourite: false,
+        palette,
+    });
+}
+
+fn parse_tags(tags: &str) -> Vec<String> {
+    tags.split(',')
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+#[component]
+pub fn Library(
+    palettes: RwSignal<Vec<SavedPalette>>,
+    active_palette: Store<Palette>,
+    selected_palette: RwSignal<Palette>,
+) -> impl IntoView {
+    let export_format = RwSignal::new(Format::Json);
+    let import_failed = RwSignal::new(false);
+    let dragging_file = RwSignal::new(false);
+    let dragged_id = RwSignal::new(None::<u64>);
+    let tag_filter = RwSignal::new(None::<String>);
+    let favourites_only = RwSignal::new(false);
+
+    let update_palette = move |id: u64, update: &dyn Fn(&mut SavedPalette)| {
+        palettes.update(|palettes| {
+            if let Some(saved) = palettes.iter_mut().find(|saved| saved.id == id) {
+                update(saved);
+            }
+        });
+    };
+
+    let all_tags = Memo::new(move |_| {
+        palettes.with(|palettes| {
+            palettes
+                .iter()
+                .flat_map(|saved| saved.tags.iter().cloned())
+                .collect::<BTreeSet<_>>()
+        })
+    });
+
+    let shown_palettes = Memo::new(move |_| {
+        let tag_filter = tag_filter.get();
+        let favourites_only = favourites_only.get();
+        palettes.with(|palettes| {
+            palettes
+                .iter()
+                .filter(|saved| !favourites_only || saved.favourite)
+                .filter(|saved| tag_filter.as_ref().is_none_or(|tag| saved.tags.contains(tag)))
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+    });
+
+    let export = move |_| {
+        let format = export_format.get_untracked();
+        if let Some(text) = active_palette.get_untracked().export(format) {
+            download_file(
+                &format!("palette.{}", format.extension()),
+                format.mime_type(),
+                text.as_bytes(),
+            );
+        }
+    };
+
+    let import_file = move |file: web_sys::File| {
+        spawn_local(async move {
+            let imported = read_file_text(&file)
+                .await
+                .and_then(|text| Palette::import(&file.name(), &text, &active_palette.get_untracked()));
+            match imported {
+                Some(palette) => {
+                    import_failed.set(false);
+                    let name = file
+                        .name()
+                        .rsplit_once('.')
+                        .map_or(file.name(), |(name, _)| name.to_string());
+                    palettes.update(|palettes| add(palettes, name, palette.clone()));
+                    selected_palette.set(palette);
+                }
+                None => import_failed.set(true),
+            }
+        });
+    };
+
+    // move the dragged palette in front of the one it is dropped on
+    let drop_palette = move |target_id: u64| {
+        if let Some(dragged_id) = dragged_id.get_untracked().filter(|&dragged_id| dragged_id != target_id) {
+            palettes.update(|palettes| {
+                if let Some(from) = palettes.iter().position(|saved| saved.id == dragged_id) {
+                    let saved = palettes.remove(from);
+                    let to = palettes
+                        .iter()
+                        .position(|saved| saved.id == target_id)
+                        .unwrap_or(palettes.len());
+                    palettes.insert(to, saved);
+                }
+            });
+        }
+        dragged_id.set(None);
+    };
+
+    let filter_class = |active: bool| {
+        if active {
+            "px-2 py-0.5 rounded-full bg-blue-600 text-white text-xs"
+        } else {
+            "px-2 py-0.5 rounded-full bg-gray-300 hover:bg-gray-400 text-gray-800 text-xs"
+        }
+    };
+
+    view! {
+        <div class="px-4 py-2 bg-gray-100 shadow-sm">
+            <div class="py-2">
+                <button
+                    on:click=move |_| {
+                        let name = format!("Palette {}", palettes.with_untracked(|palettes| palettes.len()) + 1);
+                        palettes.update(|palettes| add(palettes, name, active_palette.get_untracked()));
+                    }
+                    class="px-4 py-2 bg-green-600 hover:bg-green-500 rounded-md text-sm font-semibold transition"
+                >Save</button>
+            </div>
+            <div
+                on:dragover=move |ev: web_sys::DragEvent| {
+                    ev.prevent_default();
+                    dragging_file.set(dragged_id.get_untracked().is_none());
+                }
+                on:dragleave=move |_| dragging_file.set(false)
+                on:drop=move |ev: web_sys::DragEvent| {
+                    ev.prevent_default();
+                    dragging_file.set(false);
+                    if let Some(file) = ev.data_transfer().and_then(|data| data.files()).and_then(|files| files.get(0)) {
+                        import_file(file);
+                    }
+                }
+                class=move || format!(
+                    "flex flex-row flex-wrap gap-2 items-center py-2 px-2 mb-2 rounded-md border-2 border-dashed {}",
+                    if dragging_file.get() { "border-blue-500 bg-blue-50" } else { "border-gray-300" },
+                )
+            >
+                <select
+                    on:change=move |ev| {
+                        if let Some(format) = event_target_value(&ev).parse::<usize>().ok().and_then(|index| Format::ALL.get(index)) {
+                            export_format.set(*format);
+                        }
+                    }
+                    class="px-2 py-1 rounded-md border border-gray-300 text-sm"
+                >
+                    {Format::ALL.into_iter().enumerate().map(|(index, format)| view! {
+                        <option value=index.to_string() selected=move || export_format.get() == format>
+                            {format.label()}
+                        </option>
+                    }).collect_view()}
+                </select>
+                <button
+                    on:click=export
+                    disabled=move || {
+                        export_format.get() != Format::Json && matches!(active_palette.gradient().get(), Gradient::Wave(_))
+                    }
+                    class="px-4 py-2 bg-gray-700 hover:bg-gray-600 disabled:opacity-50 text-white rounded-md text-sm font-semibold transition"
+                >
+                    "Export"
+                </button>
+                <label class="px-4 py-2 bg-gray-700 hover:bg-gray-600 text-white rounded-md text-sm font-semibold transition cursor-pointer">
+                    "Import"
+                    <input
+                        type="file"
+                        accept=".json,.ggr,.map,.css,.txt"
+                        class="hidden"
+                        on:change=move |ev| {
+                            let input = event_target::<web_sys::HtmlInputElement>(&ev);
+                            if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                                import_file(file);
+                            }
+                            input.set_value("");
+                        }
+                    />
+                </label>
+                <span class="text-xs text-gray-500">"or drop a palette file here"</span>
+                <Show when=move || import_failed.get()>
+                    <span class="text-sm text-red-500">"Unable to read the palette file"</span>
+                </Show>
+            </div>
+
+            <div class="flex flex-row flex-wrap gap-1 mb-2">
+                <button
+                    on:click=move |_| {
+                        tag_filter.set(None);
+                        favourites_only.set(false);
+                    }
+                    class=move || filter_class(tag_filter.get().is_none() && !favourites_only.get())
+                >
+                    "All"
+                </button>
+                <button
+                    on:click=move |_| favourites_only.update(|favourites_only| *favourites_only = !*favourites_only)
+                    class=move || filter_class(favourites_only.get())
+                >
+                    "★ Favourites"
+                </button>
+                <For
+                    each=move || all_tags.get()
+                    key=|tag| tag.clone()
+                    let(tag)
+                >
+                    {
+                        let label = tag.clone();
+                        let selected = {
+                            let tag = tag.clone();
+                            move || tag_filter.with(|tag_filter| tag_filter.as_ref() == Some(&tag))
+                        };
+                        view! {
+                            <button
+                                on:click={
+                                    let selected = selected.clone();
+                                    move |_| tag_filter.set((!selected()).then(|| tag.clone()))
+                                }
+                                class=move || filter_class(selected())
+                            >
+                                {label}
+                            </button>
+                        }
+                    }
+                </For>
+            </div>
+
+            <div class="space-y-2">
+                <For
+                    each=move || shown_palettes.get()
+                    key=|saved| saved.id
+                    let(saved)
+                >
+                    {
+                        let id = saved.id;
+                        let palette = saved.palette.clone();
+                        // rows are kept while the palette is edited, so edited fields are read from the library
+                        let current = move |read: fn(&SavedPalette) -> String| {
+                            palettes.with(|palettes| palettes.iter().find(|saved| saved.id == id).map(read).unwrap_or_default())
+                        };
+                        let favourite = move || {
+                            palettes.with(|palettes| palettes.iter().any(|saved| saved.id == id && saved.favourite))
+                        };
+                        view! {
+                            <div
+                                draggable="true"
+                                on:dragstart=move |_| dragged_id.set(Some(id))
+                                on:dragend=move |_| dragged_id.set(None)
+                                on:dragover=move |ev: web_sys::DragEvent| ev.prevent_default()
+                                on:drop=move |ev: web_sys::DragEvent| {
+                                    if dragged_id.get_untracked().is_some() {
+                                        ev.prevent_default();
+                                        ev.stop_propagation();
+                                        drop_palette(id);
+                                    }
+                                }
+                                class="space-y-1"
+                            >
+                                <Preview gradient=palette.gradient.clone() length=palette.length offset=palette.offset>
+                                    <div class="w-full flex flex-row items-center justify-between gap-2 p-2">
+                                        <div class="flex flex-row items-center gap-2">
+                                            <span class="cursor-move text-white" title="Drag to reorder">"⠿"</span>
+                                            <button
+                                                on:click=move |_| update_palette(id, &|saved| saved.favourite = !saved.favourite)
+                                                class="text-lg text-yellow-300"
+                                                title="Favourite"
+                                            >
+                                                {move || if favourite() { "★" } else { "☆" }}
+                                            </button>
+                                            <input
+                                                type="text"
+                                                prop:value=move || current(|saved| saved.name.clone())
+                                                on:change=move |ev| {
+                                                    let name = event_target_value(&ev).trim().to_string();
+                                                    if !name.is_empty() {
+                                                        update_palette(id, &|saved| saved.name = name.clone());
+                                                    }
+                                                }
+                                                class="w-40 px-2 py-0.5 rounded bg-gray-900/60 text-sm font-semibold text-white"
+                                            />
+                                        </div>
+                                        <div class="flex flex-row items-center gap-2">
+                                            <button
+                                                on:click=move |_| selected_palette.set(palette.clone())
+                                                class="px-3 py-1 bg-blue-600 hover:bg-blue-500 rounded-md text-white text-sm font-medium transition"
+                                            >
+                                                Load
+                                            </button>
+                                            <button
+                                                on:click=move |_| palettes.update(|palettes| {
+                                                    if let Some(saved) = palettes.iter().find(|saved| saved.id == id).cloned() {
+                                                        add(palettes, format!("{} (copy)", saved.name), saved.palette);
+                                                        if let Some(copy) = palettes.last_mut() {
+                                                            copy.tags = saved.tags;
+                                                        }
+                                                    }
+                                                })
+                                                class="px-3 py-1 bg-gray-700 hover:bg-gray-600 rounded-md text-white text-sm font-medium transition"
+                                            >
+                                                Duplicate
+                                            </button>
+                                            <button
+                                                on:click=move |_| palettes.update(|palettes| palettes.retain(|saved| saved.id != id))
+                                                class="px-3 py-1 bg-red-600 hover:bg-red-500 rounded-md text-white text-sm font-medium transition"
+                                            >
+                                                Delete
+                                            </button>
+                                        </div>
+                                    </div>
+                                </Preview>
+                                <div class="flex flex-row items-center gap-2 text-xs text-gray-600">
+                                    <span>{format_timestamp(saved.created)}</span>
+                                    <input
+                                        type="text"
+                                        placeholder="tags, separated by commas"
+                                        prop:value=move || current(|saved| saved.tags.join(", "))
+                                        on:change=move |ev| {
+                                            let tags = parse_tags(&event_target_value(&ev));
+                                            update_palette(id, &|saved| saved.tags = tags.clone());
+                                        }
+                                        class="flex-1 px-2 py-0.5 rounded border border-gray-300"
+                                    />
+                                </div>
+                            </div>
+                        }
+                    }
+                </For>
+            </div>
+        </div>
+    }
+}
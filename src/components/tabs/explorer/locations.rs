@@ -0,0 +1,550 @@
+use std::collections::{BTreeSet, HashMap};
+
+use base64::{
+    Engine as _,
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+};
+use leptos::{prelude::*, task::spawn_local};
+use mandelbrot_explorer::{Focus, ISample};
+use send_wrapper::SendWrapper;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{
+    context::{Context, Engine, RenderSettingsStoreFields, StateStoreFields},
+    coordinates::magnification,
+    snapshot::{OffscreenRenderer, Viewport},
+    util::{download_file, format_timestamp, load_item, read_file_text},
+};
+
+use super::{export::render_png, visuals::Palette};
+
+// side of a thumbnail in pixels
+const THUMBNAIL_SIZE: u32 = 64;
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct SavedLocation {
+    pub id: u64,
+    pub name: String,
+    pub created: f64,
+    /// Folder the location is filed under, empty when it isn't filed.
+    pub folder: String,
+    pub notes: String,
+    /// PNG data URL of a small rendering of the location.
+    pub thumbnail: Option<String>,
+    pub focus: Focus,
+    pub palette: Option<Palette>,
+}
+
+/// Saved locations, taking over the ones stored by name before they had ids.
+pub fn load() -> Vec<SavedLocation> {
+    load_item::<Vec<SavedLocation>>("saved_locations").unwrap_or_else(|| {
+        let created = js_sys::Date::now();
+        let mut locations = load_item::<HashMap<String, (Focus, Option<Palette>)>>("locations")
+            .unwrap_or_default()
+            .into_iter()
+            .collect::<Vec<_>>();
+        locations.sort_by(|(a, _), (b, _)| a.cmp(b));
+        locations
+            .into_iter()
+            .enumerate()
+            .map(|(index, (name, (focus, palette)))| SavedLocation {
+                id: index as u64,
+                name,
+                created,
+                folder: String::new(),
+                notes: String::new(),
+                thumbnail: None,
+                focus,
+                palette,
+            })
+            .collect()
+    })
+}
+
+/// Add a location and return its id.
+pub fn add(locations: &mut Vec<SavedLocation>, name: String, focus: Focus, palette: Option<Palette>) -> u64 {
+    let id = locations.iter().map(|location| location.id + 1).max().unwrap_or(0);
+    locations.push(SavedLocation {
+        id,
+        name,
+        created: js_sys::Date::now(),
+        folder: String::new(),
+        notes: String::new(),
+        thumbnail: None,
+        focus,
+        palette,
+    });
+    id
+}
+
+/// Add imported locations under new ids, skipping the ones already saved with the same name and focus.
+pub fn merge(locations: &mut Vec<SavedLocation>, imported: Vec<SavedLocation>) -> usize {
+    let mut added = 0;
+    for location in imported {
+        let focus = location.focus.to_string();
+        if locations
+            .iter()
+            .any(|saved| saved.name == location.name && saved.focus.to_string() == focus)
+        {
+            continue;
+        }
+        let id = locations.iter().map(|saved| saved.id + 1).max().unwrap_or(0);
+        locations.push(SavedLocation { id, ..location });
+        added += 1;
+    }
+    added
+}
+
+/// Collection carried by a share link, without thumbnails to keep the link short.
+pub fn encode(locations: &[SavedLocation]) -> String {
+    let locations = locations
+        .iter()
+        .map(|location| SavedLocation {
+            thumbnail: None,
+            ..location.clone()
+        })
+        .collect::<Vec<_>>();
+    URL_SAFE_NO_PAD.encode(serde_json::to_string(&locations).unwrap())
+}
+
+pub fn decode(encoded: &str) -> Option<Vec<SavedLocation>> {
+    serde_json::from_slice(&URL_SAFE_NO_PAD.decode(encoded).ok()?).ok()
+}
+
+/// Render a location as it shows on a canvas of side `canvas_size`, scaled down to a thumbnail.
+async fn render_thumbnail(engine: Engine, canvas_size: u32, focus: Focus, palette: &Palette) -> Option<String> {
+    let bounds = OffscreenRenderer::locate(engine, canvas_size, focus, &palette.coloring(1.0)).await?;
+    let renderer = OffscreenRenderer::acquire(engine, THUMBNAIL_SIZE, &palette.coloring(magnification(&bounds)))?;
+    let png = render_png(
+        &renderer,
+        &Viewport::new(&bounds, THUMBNAIL_SIZE, THUMBNAIL_SIZE),
+        THUMBNAIL_SIZE,
+        THUMBNAIL_SIZE,
+        |_| true,
+    )
+    .await?;
+    Some(format!("data:image/png;base64,{}", STANDARD.encode(png)))
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Order {
+    Newest,
+    Oldest,
+    Name,
+}
+
+impl Order {
+    const ALL: [Self; 3] = [Self::Newest, Self::Oldest, Self::Name];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Newest => "Newest first",
+            Self::Oldest => "Oldest first",
+            Self::Name => "By name",
+        }
+    }
+}
+
+#[component]
+pub fn Locations(
+    locations: RwSignal<Vec<SavedLocation>>,
+    active_palette: RwSignal<Palette>,
+    selected_palette: RwSignal<Palette>,
+) -> impl IntoView {
+    let context = use_context::<SendWrapper<Context>>().unwrap();
+    let canvas = use_context::<NodeRef<leptos::html::Canvas>>().unwrap();
+    let location_name = RwSignal::new(String::new());
+    let preserve_color = RwSignal::new(true);
+    let search = RwSignal::new(String::new());
+    let order = RwSignal::new(Order::Newest);
+    let folder_filter = RwSignal::new(None::<String>);
+    let import_result = RwSignal::new(None::<Result<usize, ()>>);
+    let show_toast = RwSignal::new(false);
+
+    let update_location = move |id: u64, update: &dyn Fn(&mut SavedLocation)| {
+        locations.update(|locations| {
+            if let Some(location) = locations.iter_mut().find(|location| location.id == id) {
+                update(location);
+            }
+        });
+    };
+
+    // render a location at its own focus and palette, on offscreen engines that leave the view alone
+    let capture_thumbnail = {
+        let context = context.clone();
+        move |id: u64| {
+            let location = locations.with_untracked(|locations| {
+                locations
+                    .iter()
+                    .find(|location| location.id == id)
+                    .map(|location| (location.focus.clone(), location.palette.clone()))
+            });
+            let engine = context.state.render_settings().engine().get_untracked();
+            let canvas_size = canvas.get_untracked().map_or(THUMBNAIL_SIZE, |canvas| canvas.width());
+            let active_palette = active_palette.get_untracked();
+            async move {
+                let Some((focus, palette)) = location else {
+                    return;
+                };
+                let palette = palette.unwrap_or(active_palette);
+                if let Some(thumbnail) = render_thumbnail(engine, canvas_size, focus, &palette).await {
+                    update_location(id, &|location| location.thumbnail = Some(thumbnail.clone()));
+                }
+            }
+        }
+    };
+
+    // locations without a thumbnail, such as new, imported or migrated ones, get one rendered in turn
+    let rendering = RwSignal::new(false);
+    let attempted = StoredValue::new(BTreeSet::<u64>::new());
+    Effect::new({
+        let capture_thumbnail = capture_thumbnail.clone();
+        move || {
+            if rendering.get() {
+                return;
+            }
+            let Some(id) = locations.with(|locations| {
+                locations
+                    .iter()
+                    .find(|location| {
+                        location.thumbnail.is_none()
+                            && !attempted.with_value(|attempted| attempted.contains(&location.id))
+                    })
+                    .map(|location| location.id)
+            }) else {
+                return;
+            };
+            attempted.update_value(|attempted| {
+                attempted.insert(id);
+            });
+            rendering.set(true);
+            let capture = capture_thumbnail(id);
+            spawn_local(async move {
+                capture.await;
+                rendering.set(false);
+            });
+        }
+    });
+
+    let folders = Memo::new(move |_| {
+        locations.with(|locations| {
+            locations
+                .iter()
+                .map(|location| location.folder.clone())
+                .filter(|folder| !folder.is_empty())
+                .collect::<BTreeSet<_>>()
+        })
+    });
+
+    let shown_locations = Signal::derive(move || {
+        let search = search.get().to_lowercase();
+        let folder_filter = folder_filter.get();
+        let mut shown = locations.with(|locations| {
+            locations
+                .iter()
+                .filter(|location| folder_filter.as_ref().is_none_or(|folder| location.folder == *folder))
+                .filter(|location| {
+                    location.name.to_lowercase().contains(&search) || location.notes.to_lowercase().contains(&search)
+                })
+                .cloned()
+                .collect::<Vec<_>>()
+        });
+        match order.get() {
+            Order::Newest => shown.sort_by(|a, b| b.created.total_cmp(&a.created)),
+            Order::Oldest => shown.sort_by(|a, b| a.created.total_cmp(&b.created)),
+            Order::Name => shown.sort_by_key(|location| location.name.to_lowercase()),
+        }
+        shown
+    });
+
+    let save = {
+        let context = context.clone();
+        move |_| {
+            let name = location_name.get_untracked().trim().to_string();
+            if name.is_empty() {
+                return;
+            }
+            let focus = context.mandelbrot.lock().unwrap().engine.borrow().focus();
+            let palette = preserve_color.get_untracked().then(|| active_palette.get_untracked());
+            locations.update(|locations| {
+                add(locations, name, focus, palette);
+                if let Some(folder) = folder_filter.get_untracked() {
+                    if let Some(location) = locations.last_mut() {
+                        location.folder = folder;
+                    }
+                }
+            });
+            location_name.set(String::new());
+        }
+    };
+
+    let import_file = move |file: web_sys::File| {
+        spawn_local(async move {
+            let imported = read_file_text(&file)
+                .await
+                .and_then(|text| serde_json::from_str::<Vec<SavedLocation>>(&text).ok());
+            import_result.set(Some(match imported {
+                Some(imported) => {
+                    let mut added = 0;
+                    locations.update(|locations| added = merge(locations, imported));
+                    Ok(added)
+                }
+                None => Err(()),
+            }));
+        });
+    };
+
+    let copy_link = move |_| {
+        let Ok(href) = web_sys::window().unwrap().location().href() else {
+            return;
+        };
+        let Ok(mut url) = Url::parse(&href) else {
+            return;
+        };
+        url.set_query(Some(&format!(
+            "locations={}",
+            locations.with_untracked(|locations| encode(locations))
+        )));
+        let _ = web_sys::window()
+            .unwrap()
+            .navigator()
+            .clipboard()
+            .write_text(url.as_str());
+        show_toast.set(true);
+        set_timeout(move || show_toast.set(false), std::time::Duration::from_millis(1500));
+    };
+
+    let filter_class = |active: bool| {
+        if active {
+            "px-2 py-0.5 rounded-full bg-blue-600 text-white text-xs"
+        } else {
+            "px-2 py-0.5 rounded-full bg-gray-300 hover:bg-gray-400 text-gray-800 text-xs"
+        }
+    };
+
+    view! {
+        <div class="px-4 py-2 bg-gray-100 shadow-sm space-y-2">
+            <div class="flex flex-row flex-wrap gap-2 items-center">
+                <input
+                    type="text"
+                    placeholder="Enter location name"
+                    prop:value=move || location_name.get()
+                    on:input=move |ev| location_name.set(event_target_value(&ev))
+                    class="px-2 py-1 rounded-md border border-gray-300 text-sm"
+                />
+                <button
+                    on:click=save
+                    class="px-4 py-2 bg-green-600 hover:bg-green-500 rounded-md text-sm font-semibold transition"
+                >
+                    "Save"
+                </button>
+                <label class="flex items-center gap-2 text-sm text-gray-700">
+                    <input type="checkbox" bind:checked=preserve_color class="accent-accent1 w-4 h-4"/>
+                    "preserve color"
+                </label>
+            </div>
+
+            <div class="flex flex-row flex-wrap gap-2 items-center">
+                <button
+                    on:click=move |_| {
+                        let json = locations.with_untracked(|locations| serde_json::to_string_pretty(locations).unwrap());
+                        download_file("locations.json", "application/json", json.as_bytes());
+                    }
+                    class="px-3 py-1 bg-gray-700 hover:bg-gray-600 text-white rounded-md text-sm font-semibold transition"
+                >
+                    "Export"
+                </button>
+                <label class="px-3 py-1 bg-gray-700 hover:bg-gray-600 text-white rounded-md text-sm font-semibold transition cursor-pointer">
+                    "Import"
+                    <input
+                        type="file"
+                        accept=".json"
+                        class="hidden"
+                        on:change=move |ev| {
+                            let input = event_target::<web_sys::HtmlInputElement>(&ev);
+                            if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                                import_file(file);
+                            }
+                            input.set_value("");
+                        }
+                    />
+                </label>
+                <button
+                    on:click=copy_link
+                    class="px-3 py-1 bg-blue-600 hover:bg-blue-500 text-white rounded-md text-sm font-semibold transition"
+                >
+                    "Copy link"
+                </button>
+                {move || match import_result.get() {
+                    Some(Ok(added)) => Some(view! {
+                        <span class="text-sm text-gray-600">{format!("Imported {} locations", added)}</span>
+                    }.into_any()),
+                    Some(Err(())) => Some(view! {
+                        <span class="text-sm text-red-500">"Unable to read the locations file"</span>
+                    }.into_any()),
+                    None => None,
+                }}
+                {move || show_toast.get().then(|| view! {
+                    <span class="text-sm text-gray-500">"URL copied to clipboard"</span>
+                })}
+            </div>
+
+            <div class="flex flex-row flex-wrap gap-2 items-center">
+                <input
+                    type="search"
+                    placeholder="Search names and notes"
+                    prop:value=move || search.get()
+                    on:input=move |ev| search.set(event_target_value(&ev))
+                    class="flex-1 px-2 py-1 rounded-md border border-gray-300 text-sm"
+                />
+                <select
+                    on:change=move |ev| {
+                        if let Some(value) = event_target_value(&ev).parse::<usize>().ok().and_then(|index| Order::ALL.get(index)) {
+                            order.set(*value);
+                        }
+                    }
+                    class="px-2 py-1 rounded-md border border-gray-300 text-sm"
+                >
+                    {Order::ALL.into_iter().enumerate().map(|(index, value)| view! {
+                        <option value=index.to_string() selected=move || order.get() == value>{value.label()}</option>
+                    }).collect_view()}
+                </select>
+            </div>
+
+            <div class="flex flex-row flex-wrap gap-1">
+                <button on:click=move |_| folder_filter.set(None) class=move || filter_class(folder_filter.get().is_none())>
+                    "All"
+                </button>
+                <For each=move || folders.get() key=|folder| folder.clone() let(folder)>
+                    {
+                        let label = folder.clone();
+                        let selected = {
+                            let folder = folder.clone();
+                            move || folder_filter.with(|folder_filter| folder_filter.as_ref() == Some(&folder))
+                        };
+                        view! {
+                            <button
+                                on:click={
+                                    let selected = selected.clone();
+                                    move |_| folder_filter.set((!selected()).then(|| folder.clone()))
+                                }
+                                class=move || filter_class(selected())
+                            >
+                                {label}
+                            </button>
+                        }
+                    }
+                </For>
+            </div>
+            <datalist id="location-folders">
+                {move || folders.get().into_iter().map(|folder| view! { <option value=folder/> }).collect_view()}
+            </datalist>
+
+            <div class="space-y-2">
+                <For
+                    each=move || shown_locations.get()
+                    key=|location| location.id
+                    let(location)
+                >
+                    {
+                        let id = location.id;
+                        let context = context.clone();
+                        let capture_thumbnail = capture_thumbnail.clone();
+                        // rows are kept while the location is edited, so edited fields are read from the collection
+                        let current = move |read: fn(&SavedLocation) -> String| {
+                            locations.with(|locations| locations.iter().find(|location| location.id == id).map(read).unwrap_or_default())
+                        };
+                        let thumbnail = move || {
+                            locations.with(|locations| {
+                                locations.iter().find(|location| location.id == id).and_then(|location| location.thumbnail.clone())
+                            })
+                        };
+                        view! {
+                            <div class="flex flex-row gap-3 p-2 rounded-md bg-white shadow-sm">
+                                {move || match thumbnail() {
+                                    Some(thumbnail) => view! {
+                                        <img src=thumbnail alt=move || current(|location| location.name.clone()) class="w-16 h-16 rounded shrink-0"/>
+                                    }.into_any(),
+                                    None => view! {
+                                        <div class="w-16 h-16 rounded shrink-0 bg-gray-800 text-gray-400 text-xs flex items-center justify-center text-center">
+                                            "No preview"
+                                        </div>
+                                    }.into_any(),
+                                }}
+                                <div class="flex-1 min-w-0 space-y-1">
+                                    <div class="flex flex-row flex-wrap items-center gap-2">
+                                        <input
+                                            type="text"
+                                            prop:value=move || current(|location| location.name.clone())
+                                            on:change=move |ev| {
+                                                let name = event_target_value(&ev).trim().to_string();
+                                                if !name.is_empty() {
+                                                    update_location(id, &|location| location.name = name.clone());
+                                                }
+                                            }
+                                            class="flex-1 min-w-0 px-2 py-0.5 rounded border border-gray-300 text-sm font-semibold"
+                                        />
+                                        <span class="text-xs text-gray-500">{format_timestamp(location.created)}</span>
+                                    </div>
+                                    <div class="flex flex-row flex-wrap items-center gap-2">
+                                        <input
+                                            type="text"
+                                            list="location-folders"
+                                            placeholder="Folder"
+                                            prop:value=move || current(|location| location.folder.clone())
+                                            on:change=move |ev| {
+                                                let folder = event_target_value(&ev).trim().to_string();
+                                                update_location(id, &|location| location.folder = folder.clone());
+                                            }
+                                            class="w-32 px-2 py-0.5 rounded border border-gray-300 text-xs"
+                                        />
+                                        <button
+                                            on:click=move |_| {
+                                                let Some(location) = locations.with_untracked(|locations| {
+                                                    locations.iter().find(|location| location.id == id).cloned()
+                                                }) else {
+                                                    return;
+                                                };
+                                                if let Some(palette) = location.palette {
+                                                    selected_palette.set(palette);
+                                                }
+                                                context.mandelbrot.lock().unwrap().move_into_focus(location.focus);
+                                            }
+                                            class="px-3 py-1 bg-blue-600 hover:bg-blue-500 rounded-md text-white text-sm font-medium transition"
+                                        >
+                                            "Zoom"
+                                        </button>
+                                        <button
+                                            on:click=move |_| spawn_local(capture_thumbnail(id))
+                                            class="px-3 py-1 bg-gray-700 hover:bg-gray-600 rounded-md text-white text-sm font-medium transition"
+                                            title="Render the thumbnail again, with the location's palette"
+                                        >
+                                            "Thumbnail"
+                                        </button>
+                                        <button
+                                            on:click=move |_| locations.update(|locations| locations.retain(|location| location.id != id))
+                                            class="px-3 py-1 bg-red-600 hover:bg-red-500 rounded-md text-white text-sm font-medium transition"
+                                        >
+                                            "Delete"
+                                        </button>
+                                    </div>
+                                    <textarea
+                                        placeholder="Notes"
+                                        prop:value=move || current(|location| location.notes.clone())
+                                        on:change=move |ev| {
+                                            let notes = event_target_value(&ev);
+                                            update_location(id, &|location| location.notes = notes.clone());
+                                        }
+                                        rows=2
+                                        class="w-full px-2 py-0.5 rounded border border-gray-300 text-xs"
+                                    />
+                                </div>
+                            </div>
+                        }
+                    }
+                </For>
+            </div>
+        </div>
+    }
+}
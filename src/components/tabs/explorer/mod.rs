@@ -1,44 +1,109 @@
+mod export;
+mod flythrough;
 mod gradient;
+mod library;
+mod locations;
+mod rendering;
+mod share;
 mod visuals;
 
-use std::{collections::HashMap, time::Duration};
-
 use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
 use leptos::prelude::*;
-use leptos_router::hooks::use_query_map;
-use mandelbrot_explorer::{Focus, ISample};
-use reactive_stores::Store;
+use leptos_router::hooks::{use_navigate, use_query_map};
 use send_wrapper::SendWrapper;
 
-use url::Url;
 use visuals::Visuals;
 
 use crate::{
-    color::Gradient,
     context::Context,
-    util::{load_item, store_item},
+    evm::contracts,
+    share::Shared,
+    util::{load_item, preserve_log_level, store_item},
 };
+use export::{ImageExport, Snapshot};
+use flythrough::Flythrough;
+use locations::Locations;
+use rendering::Rendering;
+use share::ShareDialog;
 use visuals::Palette;
 
 #[component]
 pub fn Explorer() -> impl IntoView {
     let query_map = use_query_map();
     let context = use_context::<SendWrapper<Context>>().unwrap();
-    let selected_palette = RwSignal::new({
-        if let Some(palette) = query_map.get_untracked().get("palette") {
-            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(&palette).unwrap()).unwrap()
-        } else {
+    let error = use_context::<WriteSignal<Option<contracts::Error>>>().unwrap();
+    // palette of a share link, or of a link from before share links were versioned
+    let shared_palette = match use_context::<Option<Result<Shared, crate::share::Error>>>().flatten() {
+        Some(Ok(shared)) => shared.palette.as_deref().map(Palette::from_bytes).transpose(),
+        Some(Err(share_error)) => Err(share_error),
+        None => query_map
+            .get_untracked()
+            .get("palette")
+            .map(|palette| {
+                URL_SAFE_NO_PAD
+                    .decode(&palette)
+                    .ok()
+                    .and_then(|palette| serde_json::from_slice(&palette).ok())
+                    .ok_or(crate::share::Error::Malformed)
+            })
+            .transpose(),
+    };
+    let selected_palette = RwSignal::new(match shared_palette {
+        Ok(Some(palette)) => palette,
+        Ok(None) => load_item::<Palette>("active_palette").unwrap_or_default(),
+        Err(share_error) => {
+            error.set(Some(contracts::Error::Other(share_error.to_string())));
             load_item::<Palette>("active_palette").unwrap_or_default()
         }
     });
     let active_palette = RwSignal::new(Palette::default());
-    let show_toast = RwSignal::new(false);
+    let share_open = RwSignal::new(false);
 
-    let locations =
-        RwSignal::new(load_item::<HashMap<String, (Focus, Option<Palette>)>>("locations").unwrap_or_default());
-    let location_name = RwSignal::new(String::new());
-    let preserve_color = RwSignal::new(true);
-    let store_locations = move || store_item("locations", &locations.get_untracked());
+    let locations = RwSignal::new(locations::load());
+    // a shared collection is added to the saved locations once the user agrees to
+    if let Some(shared) = query_map
+        .get_untracked()
+        .get("locations")
+        .and_then(|shared| locations::decode(&shared))
+        .filter(|shared| {
+            web_sys::window()
+                .and_then(|window| {
+                    window
+                        .confirm_with_message(&format!(
+                            "Add the {} shared locations in this link to your saved locations?",
+                            shared.len()
+                        ))
+                        .ok()
+                })
+                .unwrap_or(false)
+        })
+    {
+        locations.update(|locations| {
+            locations::merge(locations, shared);
+        });
+    }
+    Effect::new(move || store_item("saved_locations", &locations.get()));
+
+    // an exported image is saved as a location and opened
+    let open_snapshot = {
+        let context = context.clone();
+        let navigate = use_navigate();
+        move |name: String, snapshot: Snapshot| {
+            locations.update(|locations| {
+                locations::add(locations, name, snapshot.focus.clone(), snapshot.palette.clone());
+            });
+            if let Some(palette) = snapshot.palette {
+                selected_palette.set(palette);
+            }
+            if let Some(token_id) = snapshot.token_id {
+                navigate(
+                    &preserve_log_level(format!("/tokens/{}", token_id), query_map),
+                    Default::default(),
+                );
+            }
+            context.mandelbrot.lock().unwrap().move_into_focus(snapshot.focus);
+        }
+    };
 
     view! {
         <div class="flex flex-col">
@@ -48,160 +113,39 @@ pub fn Explorer() -> impl IntoView {
                 <summary class="cursor-pointer px-2 py-2 bg-gray-100 hover:bg-gray-200">
                     Saved Locations
                 </summary>
-                <div class="px-4 py-2 bg-gray-100 shadow-sm">
-                    <div class="flex flex-row gap-2 items-center">
-                        <input
-                            type="text"
-                            placeholder="Enter localion name"
-                            prop:value=move || location_name.get()
-                            on:input=move |ev| location_name.set(event_target_value(&ev)) />
-                        <button
-                            on:click={
-                                let context = context.clone();
-                                move |_| {
-                                    let name = location_name.get_untracked().trim().to_string();
-                                    if !name.is_empty() {
-                                        let focus = context.mandelbrot.lock().unwrap().engine.borrow().focus();
-                                        locations.update(|locations| {
-                                            locations.insert(name, (focus, preserve_color.get().then(|| {
-                                                active_palette.get()
-                                            })));
-                                        });
-                                        store_locations();
-                                        location_name.set(String::new());
-                                    }
-                                }
-                            }
-                            class="px-4 py-2 bg-green-600 hover:bg-green-500 rounded-md text-sm font-semibold transition"
-                        >Save</button>
-                        <input
-                            type="checkbox"
-                            bind:checked=preserve_color
-                            class="accent-accent1 w-4 h-4"
-                        />
-                        <label class="text-sm text-gray-700">preserve color</label>
-                    </div>
-                        {
-                            let context = context.clone();
-                            view! {
-                                <For
-                                    each=move || locations.get()
-                                    key=|(name, (focus, _))| (name.clone(), focus.to_string())
-                                    let((name, (focus, palette)))
-                                >
-                                    {
-                                        let context = context.clone();
-                                        if let Some(palette) = palette.clone() {
-                                            match palette.gradient.clone() {
-                                                Gradient::Wave(_) => todo!(),
-                                                Gradient::Step(gradient) => view! {
-                                                    <gradient::step::Bar
-                                                        position=RwSignal::new(0.0).write_only()
-                                                        width=RwSignal::new(0.0).write_only()
-                                                        points=Store::new(gradient.into())
-                                                        on_click=|_| {}
-                                                        length=palette.length
-                                                        offset=palette.offset
-                                                    >
-                                                        <Location
-                                                            name=name.clone()
-                                                            on_select=move || {
-                                                                selected_palette.set(palette.clone());
-                                                                context.mandelbrot.lock().unwrap().move_into_focus(focus.clone());
-                                                            }
-                                                            on_delete=move || {
-                                                                locations.update(|locations| { locations.remove(&name); });
-                                                                store_locations();
-                                                            }
-                                                        />
-                                                    </gradient::step::Bar>
-                                                }.into_any(),
-                                            }
-                                        } else {
-                                            view! {
-                                                <div class="flex flex-row items-center h-12 rounded-md border border-gray-700 bg-gray-900/50">
-                                                    <Location
-                                                        name=name.clone()
-                                                        on_select=move || {
-                                                            palette.clone().map(|palette| selected_palette.set(palette));
-                                                            context.mandelbrot.lock().unwrap().move_into_focus(focus.clone());
-                                                        }
-                                                        on_delete=move || {
-                                                            locations.update(|locations| { locations.remove(&name); });
-                                                            store_locations();
-                                                        }
-                                                    />
-                                                </div>
-                                            }.into_any()
-                                        }
-                                    }
-                                </For>
-                            }
-                        }
-                </div>
+                <Locations locations active_palette selected_palette/>
             </details>
 
-            <div class="flex flex-row my-1">
-                <button
-                    on:click={
-                        let context = context.clone();
-                        move |_| {
-                            if let Ok(href) = web_sys::window().unwrap().location().href() {
-                                let mut url = Url::parse(&href).ok().unwrap();
-                                url.set_query(Some(&format!(
-                                    "focus={}&palette={}",
-                                    context.mandelbrot.lock().unwrap().engine.borrow().focus(),
-                                    URL_SAFE_NO_PAD.encode(serde_json::to_string(&active_palette.get_untracked()).unwrap()),
-                                )));
-                                let clipboard = web_sys::window()
-                                    .unwrap()
-                                    .navigator()
-                                    .clipboard();
-
-                                let _ = clipboard.write_text(&url.to_string());
+            <details class="border-b w-full text-gray-700 rounded-md">
+                <summary class="cursor-pointer px-2 py-2 bg-gray-100 hover:bg-gray-200">
+                    Flythrough
+                </summary>
+                <Flythrough locations active_palette selected_palette/>
+            </details>
 
-                                show_toast.set(true);
-                                let show_toast = show_toast.clone();
-                                set_timeout(move || show_toast.set(false), Duration::from_millis(1500));
-                            }
-                        }
-                    }
-                    class="px-3 py-1 bg-blue-600 hover:bg-blue-500 rounded-md text-white text-sm font-medium transition"
-                >
-                    Share
-                </button>
+            <details class="border-b w-full text-gray-700 rounded-md">
+                <summary class="cursor-pointer px-2 py-2 bg-gray-100 hover:bg-gray-200">
+                    Export Image
+                </summary>
+                <ImageExport palette=active_palette on_open=open_snapshot/>
+            </details>
 
-                {move || show_toast.get().then(|| view! {
-                    <div class="mx-10 px-3 text-gray-500">
-                        URL copied to clipboard
-                    </div>
-                })}
-            </div>
-        </div>
-    }
-}
+            <details class="border-b w-full text-gray-700 rounded-md">
+                <summary class="cursor-pointer px-2 py-2 bg-gray-100 hover:bg-gray-200">
+                    Rendering
+                </summary>
+                <Rendering/>
+            </details>
 
-#[component]
-pub fn Location(name: String, on_select: impl Fn() + 'static, on_delete: impl Fn() + 'static) -> impl IntoView {
-    view! {
-        <div class="w-full flex flex-row items-center justify-between gap-4 p-4">
-            <div class="text-sm text-white">
-                <div class="font-semibold">{name}</div>
-            </div>
-            <div class="flex flex-wrap gap-2">
+            <div class="flex flex-row my-1">
                 <button
-                    on:click=move |_| on_select()
+                    on:click=move |_| share_open.set(true)
                     class="px-3 py-1 bg-blue-600 hover:bg-blue-500 rounded-md text-white text-sm font-medium transition"
                 >
-                    Zoom
-                </button>
-                <button
-                    on:click=move |_| on_delete()
-                    class="px-3 py-1 bg-red-600 hover:bg-red-500 rounded-md text-white text-sm font-medium transition"
-                >
-                    Delete
+                    Share
                 </button>
             </div>
+            <ShareDialog open=share_open palette=active_palette/>
         </div>
     }
 }
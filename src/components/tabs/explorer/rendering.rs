@@ -0,0 +1,112 @@
+use leptos::prelude::*;
+use leptos_use::{UseRafFnCallbackArgs, use_raf_fn};
+use send_wrapper::SendWrapper;
+
+use crate::{
+    components::primitive::Slider,
+    context::{Context, Engine, RenderSettingsStoreFields, StateStoreFields},
+};
+
+// weight of the latest frame in the averaged frame interval
+const SMOOTHING: f64 = 0.1;
+
+#[component]
+pub fn Rendering() -> impl IntoView {
+    let context = use_context::<SendWrapper<Context>>().unwrap();
+    let canvas = use_context::<NodeRef<leptos::html::Canvas>>().unwrap();
+    let settings = context.state.render_settings();
+
+    let match_display = RwSignal::new(settings.resolution_scale().get_untracked().is_none());
+    let scale = RwSignal::new(
+        settings
+            .resolution_scale()
+            .get_untracked()
+            .unwrap_or_else(|| web_sys::window().unwrap().device_pixel_ratio()),
+    );
+    Effect::new(move || {
+        let resolution_scale = (!match_display.get()).then(|| scale.get());
+        if settings.resolution_scale().get_untracked() != resolution_scale {
+            settings.resolution_scale().set(resolution_scale);
+        }
+    });
+
+    // time between animation frames, which bounds how often the engine can draw rather than measuring its work
+    let frame_interval = RwSignal::new(0.0);
+    let canvas_size = RwSignal::new((0, 0));
+    let max_iterations = RwSignal::new(0);
+    use_raf_fn({
+        let mandelbrot = context.mandelbrot.clone();
+        move |UseRafFnCallbackArgs { delta, .. }| {
+            frame_interval.update(|frame_interval| *frame_interval += (delta - *frame_interval) * SMOOTHING);
+            let size = canvas
+                .get_untracked()
+                .map_or((0, 0), |canvas| (canvas.width(), canvas.height()));
+            if canvas_size.get_untracked() != size {
+                canvas_size.set(size);
+            }
+            let iterations = mandelbrot.lock().unwrap().palette.max_iterations;
+            if max_iterations.get_untracked() != iterations {
+                max_iterations.set(iterations);
+            }
+        }
+    });
+
+    view! {
+        <div class="px-4 py-2 bg-gray-100 shadow-sm space-y-3">
+            <div class="space-y-1">
+                <p class="text-sm font-medium">"Engine"</p>
+                {Engine::ALL.into_iter().map(|engine| view! {
+                    <label class="flex items-start gap-2 text-sm cursor-pointer">
+                        <input
+                            type="radio"
+                            name="engine"
+                            prop:checked=move || settings.engine().get() == engine
+                            on:change=move |_| settings.engine().set(engine)
+                            class="accent-accent1 mt-1"
+                        />
+                        <span>
+                            <span class="font-semibold">{engine.label()}</span>
+                            <span class="block text-xs text-gray-500">{engine.description()}</span>
+                        </span>
+                    </label>
+                }).collect_view()}
+            </div>
+
+            <div class="space-y-1">
+                <div class="flex justify-between items-center">
+                    <label class="text-sm font-medium">"Quality"</label>
+                    <span class="text-sm font-mono">
+                        {move || format!("{:.2} pixels per point", settings.resolution_scale().get().unwrap_or(scale.get()))}
+                    </span>
+                </div>
+                <label class="flex items-center gap-2 text-sm">
+                    <input type="checkbox" bind:checked=match_display class="accent-accent1 w-4 h-4"/>
+                    "Match the display"
+                </label>
+                <Show when=move || !match_display.get()>
+                    <Slider
+                        min=0.25
+                        max=3.0
+                        value=scale
+                        class="w-full bg-gray-300 rounded-full focus:outline-none"
+                    />
+                    <p class="text-xs text-gray-500">"Lower values render faster but blurrier"</p>
+                </Show>
+            </div>
+
+            <div class="grid grid-cols-2 gap-x-4 text-sm">
+                <span class="text-gray-500">"Display frame interval"</span>
+                <span class="font-mono">
+                    {move || format!("{:.1} ms ({:.0} fps)", frame_interval.get(), 1000.0 / frame_interval.get().max(1.0))}
+                </span>
+                <span class="text-gray-500">"Resolution"</span>
+                <span class="font-mono">{move || { let (width, height) = canvas_size.get(); format!("{}×{}", width, height) }}</span>
+                <span class="text-gray-500">"Iteration limit"</span>
+                <span class="font-mono">{move || max_iterations.get()}</span>
+            </div>
+            <p class="text-xs text-gray-500">
+                "The engine doesn't report its render time, the iterations it used or the length of its reference orbit"
+            </p>
+        </div>
+    }
+}
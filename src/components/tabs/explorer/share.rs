@@ -0,0 +1,154 @@
+use std::time::Duration;
+
+use leptos::prelude::*;
+use mandelbrot_explorer::ISample;
+use qrcode::{QrCode, render::svg};
+use send_wrapper::SendWrapper;
+use url::Url;
+
+use crate::{
+    color::Gradient,
+    context::{Context, StateStoreFields},
+    coordinates::{center, magnification, to_f64},
+    share::{self, Shared},
+};
+
+use super::visuals::Palette;
+
+// smallest side of the QR code in pixels
+const QR_SIZE: u32 = 200;
+
+/// One sentence describing the view, for people reading the link before opening it.
+fn describe(context: &Context, palette: Option<&Palette>, token_id: Option<u128>) -> String {
+    let bounds = context.mandelbrot.lock().unwrap().engine.borrow().get_bounds();
+    let (x, y) = center(&bounds);
+    let (x, y) = (to_f64(&x), to_f64(&y));
+    let mut description = format!(
+        "Centered on {:.6} {} {:.6}i at {:.3e}× magnification",
+        x,
+        if y < 0.0 { "−" } else { "+" },
+        y.abs(),
+        magnification(&bounds),
+    );
+    if let Some(token_id) = token_id {
+        description += &format!(", inside NFT #{}", token_id);
+    }
+    if let Some(palette) = palette {
+        description += match palette.gradient {
+            Gradient::Wave(_) => ", with a wave palette",
+            Gradient::Step(_) => ", with a step palette",
+        };
+    }
+    description
+}
+
+fn qr_code(link: &str) -> Option<String> {
+    let code = QrCode::new(link.as_bytes()).ok()?;
+    Some(
+        code.render::<svg::Color>()
+            .min_dimensions(QR_SIZE, QR_SIZE)
+            .dark_color(svg::Color("#000000"))
+            .light_color(svg::Color("#ffffff"))
+            .build(),
+    )
+}
+
+#[component]
+pub fn ShareDialog(open: RwSignal<bool>, palette: RwSignal<Palette>) -> impl IntoView {
+    let context = use_context::<SendWrapper<Context>>().unwrap();
+    let include_palette = RwSignal::new(true);
+    let include_token = RwSignal::new(true);
+    let show_toast = RwSignal::new(false);
+    let state = context.state;
+
+    let link = Memo::new(move |_| {
+        if !open.get() {
+            return None;
+        }
+        let shared = Shared {
+            focus: state.focus().get(),
+            palette: include_palette.get().then(|| palette.get().to_bytes()),
+            token_id: include_token.get().then(|| state.current_token_id().get()).flatten(),
+            tab: Some(state.selected_tab().get()),
+        };
+        let href = web_sys::window()?.location().href().ok()?;
+        let mut url = Url::parse(&href).ok()?;
+        url.set_query(Some(&format!("share={}", share::encode(&shared))));
+        Some(url.to_string())
+    });
+
+    let description = move || {
+        state.focus().track();
+        describe(
+            &context,
+            include_palette.get().then(|| palette.get()).as_ref(),
+            include_token.get().then(|| state.current_token_id().get()).flatten(),
+        )
+    };
+
+    let copy = move |_| {
+        if let Some(link) = link.get_untracked() {
+            let _ = web_sys::window().unwrap().navigator().clipboard().write_text(&link);
+            show_toast.set(true);
+            set_timeout(move || show_toast.set(false), Duration::from_millis(1500));
+        }
+    };
+
+    view! {
+        <Show when=move || open.get()>
+            <div class="fixed inset-0 z-50 flex items-center justify-center bg-black/50" on:click=move |_| open.set(false)>
+                <div
+                    class="w-full max-w-md max-h-[90vh] overflow-y-auto bg-gray-900 text-white rounded-lg shadow-xl p-4 space-y-3"
+                    on:click=|ev| ev.stop_propagation()
+                >
+                    <div class="text-lg font-semibold border-b border-gray-700 pb-2">"Share this view"</div>
+                    <p class="text-sm text-gray-300">{description.clone()}</p>
+                    <div class="flex flex-row gap-4 text-sm">
+                        <label class="flex items-center gap-2">
+                            <input type="checkbox" bind:checked=include_palette class="accent-accent1 w-4 h-4"/>
+                            "Palette"
+                        </label>
+                        <label class="flex items-center gap-2">
+                            <input type="checkbox" bind:checked=include_token class="accent-accent1 w-4 h-4"/>
+                            "NFT"
+                        </label>
+                    </div>
+                    <div class="flex flex-row gap-2">
+                        <input
+                            type="text"
+                            readonly
+                            prop:value=move || link.get().unwrap_or_default()
+                            on:focus=|ev| event_target::<web_sys::HtmlInputElement>(&ev).select()
+                            class="flex-1 min-w-0 px-2 py-1 rounded bg-gray-800 border border-gray-600 font-mono text-xs"
+                        />
+                        <button
+                            on:click=copy
+                            class="px-3 py-1 bg-blue-600 hover:bg-blue-500 rounded-md text-sm font-medium transition"
+                        >
+                            "Copy"
+                        </button>
+                    </div>
+                    <Show when=move || show_toast.get()>
+                        <div class="text-sm text-gray-400">"URL copied to clipboard"</div>
+                    </Show>
+                    <div class="flex justify-center">
+                        {move || match link.get().and_then(|link| qr_code(&link)) {
+                            Some(svg) => view! { <div class="rounded bg-white p-2" inner_html=svg/> }.into_any(),
+                            None => view! {
+                                <p class="text-sm text-gray-400">"The link is too long for a QR code, try leaving the palette out"</p>
+                            }.into_any(),
+                        }}
+                    </div>
+                    <div class="flex justify-end pt-2 border-t border-gray-700">
+                        <button
+                            on:click=move |_| open.set(false)
+                            class="px-4 py-2 bg-gray-700 hover:bg-gray-600 rounded-md text-sm font-medium transition"
+                        >
+                            "Close"
+                        </button>
+                    </div>
+                </div>
+            </div>
+        </Show>
+    }
+}
@@ -1,28 +1,240 @@
 use leptos::prelude::*;
+use leptos_use::{UseRafFnCallbackArgs, UseRafFnOptions, use_raf_fn_with_options};
 use reactive_stores::Store;
 use send_wrapper::SendWrapper;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    color::{Gradient, StepGradient},
+    color::{Checkpoint, Gradient, Interpolation, Preset, StepGradient, Wave, WaveGradient, presets},
     components::primitive::Slider,
-    context::Context,
-    util::{load_item, store_item},
+    context::{Context, StateStoreFields},
+    coordinates::magnification,
+    palette_file::{self, Format},
+    share::{self, Reader, Writer},
+    snapshot::Coloring,
+    util::store_item,
 };
 
-use super::gradient::{step, wave};
+use super::{
+    gradient::{Preview, step, wave},
+    library::{self, Library},
+};
+
+/// Iteration limit derived from the zoom depth, so that deep views don't turn black.
+#[derive(Clone, Copy, Deserialize, PartialEq, Serialize, Store)]
+pub struct AutoIterations {
+    enabled: bool,
+    multiplier: f64,
+    min: f64,
+    max: f64,
+}
+
+impl AutoIterations {
+    pub(super) fn iterations(&self, magnification: f64) -> u32 {
+        let depth = magnification.log2().max(0.0);
+        (self.multiplier * (100.0 + 50.0 * depth)).clamp(self.min, self.max.max(self.min)) as u32
+    }
+}
 
-#[derive(Clone, Deserialize, Serialize, Store)]
+impl Default for AutoIterations {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            multiplier: 1.0,
+            min: 100.0,
+            max: 50_000.0,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, PartialEq, Serialize, Store)]
 pub struct Palette {
     pub(super) gradient: Gradient,
-    max_iterations: f64,
+    pub(super) max_iterations: f64,
     pub(super) offset: f64,
     pub(super) length: f64,
+    /// Color offset change per second while cycling, negative values cycle backwards.
+    #[serde(default)]
+    cycle_speed: f64,
+    #[serde(default)]
+    cycle_ping_pong: bool,
+    #[serde(default)]
+    auto_iterations: AutoIterations,
 }
 
 impl Palette {
-    fn key(&self) -> String {
-        serde_json::to_string(self).unwrap()
+    /// Wave gradients can only be exported as JSON.
+    pub(super) fn export(&self, format: Format) -> Option<String> {
+        match (&self.gradient, format) {
+            (_, Format::Json) => Some(serde_json::to_string_pretty(self).unwrap()),
+            (Gradient::Step(gradient), format) => Some(palette_file::export(gradient, format)),
+            (Gradient::Wave(_), _) => None,
+        }
+    }
+
+    /// Read a palette file, guessing its format from the file name. Formats that only describe a gradient take the
+    /// other settings from `settings`.
+    pub(super) fn import(file_name: &str, text: &str, settings: &Palette) -> Option<Self> {
+        if let Ok(palette) = serde_json::from_str::<Palette>(text) {
+            return Some(palette);
+        }
+        let formats = Format::from_file_name(file_name)
+            .map(|format| vec![format])
+            .unwrap_or(Format::ALL.to_vec());
+        formats
+            .into_iter()
+            .find_map(|format| palette_file::import(text, format))
+            .map(|gradient| Self {
+                gradient: gradient.into(),
+                ..settings.clone()
+            })
+    }
+
+    /// Binary form carried by share links, see [`share`]. Numbers are rounded to f32 on the way.
+    pub(super) fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::default();
+        match &self.gradient {
+            Gradient::Wave(gradient) => {
+                writer.u8(0);
+                for wave in [gradient.red, gradient.green, gradient.blue] {
+                    for value in [wave.bias, wave.amplitude, wave.frequency, wave.phase] {
+                        writer.f32(value);
+                    }
+                }
+            }
+            Gradient::Step(gradient) => {
+                writer.u8(1);
+                writer.u8(Interpolation::ALL
+                    .iter()
+                    .position(|interpolation| *interpolation == gradient.interpolation)
+                    .unwrap() as u8);
+                writer.varint(gradient.checkpoints.len() as u128);
+                for checkpoint in &gradient.checkpoints {
+                    writer.f32(checkpoint.position);
+                    checkpoint.color.into_iter().for_each(|channel| writer.u8(channel));
+                }
+            }
+        }
+        for value in [self.max_iterations, self.offset, self.length, self.cycle_speed] {
+            writer.f32(value);
+        }
+        writer.u8(self.cycle_ping_pong as u8 | (self.auto_iterations.enabled as u8) << 1);
+        if self.auto_iterations.enabled {
+            for value in [
+                self.auto_iterations.multiplier,
+                self.auto_iterations.min,
+                self.auto_iterations.max,
+            ] {
+                writer.f32(value);
+            }
+        }
+        writer.finish()
+    }
+
+    pub(super) fn from_bytes(bytes: &[u8]) -> Result<Self, share::Error> {
+        let mut reader = Reader::new(bytes);
+        let gradient = match reader.u8()? {
+            0 => {
+                let mut wave =
+                    || Ok::<_, share::Error>(Wave::new(reader.f32()?, reader.f32()?, reader.f32()?, reader.f32()?));
+                Gradient::Wave(WaveGradient {
+                    red: wave()?,
+                    green: wave()?,
+                    blue: wave()?,
+                })
+            }
+            1 => {
+                let interpolation = *Interpolation::ALL
+                    .get(reader.u8()? as usize)
+                    .ok_or(share::Error::Invalid)?;
+                let count = reader.varint()?;
+                let mut checkpoints = Vec::new();
+                for _ in 0..count {
+                    checkpoints.push(Checkpoint {
+                        position: reader.f32()?,
+                        color: [reader.u8()?, reader.u8()?, reader.u8()?],
+                    });
+                }
+                Gradient::Step(StepGradient {
+                    checkpoints,
+                    interpolation,
+                })
+            }
+            _ => return Err(share::Error::Invalid),
+        };
+        let mut palette = Self {
+            gradient,
+            max_iterations: reader.f32()?,
+            offset: reader.f32()?,
+            length: reader.f32()?,
+            cycle_speed: reader.f32()?,
+            ..Default::default()
+        };
+        let flags = reader.u8()?;
+        palette.cycle_ping_pong = flags & 1 != 0;
+        if flags & 2 != 0 {
+            palette.auto_iterations = AutoIterations {
+                enabled: true,
+                multiplier: reader.f32()?,
+                min: reader.f32()?,
+                max: reader.f32()?,
+            };
+        }
+        if reader.is_empty() {
+            Ok(palette)
+        } else {
+            Err(share::Error::Invalid)
+        }
+    }
+
+    /// Iteration limit for a view at the given magnification.
+    pub(super) fn iterations(&self, magnification: f64) -> u32 {
+        if self.auto_iterations.enabled {
+            self.auto_iterations.iterations(magnification)
+        } else {
+            self.max_iterations.powi(2) as u32
+        }
+    }
+
+    pub(super) fn coloring(&self, magnification: f64) -> Coloring {
+        Coloring {
+            gradient: self.gradient.clone(),
+            max_iterations: self.iterations(magnification),
+            offset: self.offset,
+            length: self.length,
+        }
+    }
+
+    /// Palette `t` of the way from this one to `other`, for transitions between locations.
+    pub(super) fn blend(&self, other: &Palette, t: f64) -> Palette {
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+        Self {
+            gradient: self.gradient.blend(&other.gradient, t),
+            max_iterations: lerp(self.max_iterations, other.max_iterations),
+            offset: lerp(self.offset, other.offset),
+            length: lerp(self.length, other.length),
+            ..self.clone()
+        }
+    }
+
+    /// Show this palette in the renderer, without going through the palette editor.
+    pub(super) fn apply(&self, palette: &mut mandelbrot_explorer::Palette, magnification: f64) {
+        palette.gradient = self.gradient.clone().into();
+        palette.max_iterations = self.iterations(magnification) as i32;
+        palette.offset = self.offset as f32;
+        palette.length = self.length as f32;
+    }
+}
+
+impl From<Preset> for Palette {
+    fn from(preset: Preset) -> Self {
+        Self {
+            gradient: preset.gradient,
+            max_iterations: preset.max_iterations.sqrt(),
+            offset: preset.offset,
+            length: preset.length,
+            ..Self::default()
+        }
     }
 }
 
@@ -33,6 +245,9 @@ impl Default for Palette {
             max_iterations: 40.0,
             offset: 0.0,
             length: 100.0,
+            cycle_speed: 0.0,
+            cycle_ping_pong: false,
+            auto_iterations: AutoIterations::default(),
         }
     }
 }
@@ -43,27 +258,97 @@ pub fn Visuals(palette: RwSignal<Palette>, on_update: impl Fn(Palette) + 'static
     let selected_palette = palette;
     let active_palette = Store::new(Palette::default());
 
-    let palettes = RwSignal::new(load_item::<Vec<Palette>>("palettes").unwrap_or_default());
-    let store_palettes = move || store_item("palettes", &palettes.get_untracked());
+    let palettes = RwSignal::new(library::load());
 
     Effect::new(move || active_palette.set(selected_palette.get()));
     Effect::new(move || store_item("active_palette", &active_palette.get()));
+    Effect::new(move || store_item("palette_library", &palettes.get()));
     Effect::new(move || on_update(active_palette.get()));
+
+    let effective_iterations = Memo::new({
+        let context = context.clone();
+        move |_| {
+            let auto_iterations = active_palette.auto_iterations().get();
+            if auto_iterations.enabled {
+                context.state.focus().track();
+                let bounds = context.mandelbrot.lock().unwrap().engine.borrow().get_bounds();
+                auto_iterations.iterations(magnification(&bounds))
+            } else {
+                active_palette.max_iterations().get().powi(2) as u32
+            }
+        }
+    });
+    // the iteration limit follows the view, so it is written on its own to leave a cycling offset alone
+    Effect::new({
+        let mandelbrot = context.mandelbrot.clone();
+        move || {
+            let max_iterations = effective_iterations.get() as i32;
+            let mut mandelbrot = mandelbrot.lock().unwrap();
+            mandelbrot.palette.max_iterations = max_iterations;
+            if let Some(redraw) = &mandelbrot.redraw {
+                redraw();
+            }
+        }
+    });
     Effect::new({
         let mandelbrot = context.mandelbrot.clone();
         move || {
+            let (offset, length) = (active_palette.offset().get(), active_palette.length().get());
             let mut mandelbrot = mandelbrot.lock().unwrap();
-            mandelbrot.palette.max_iterations = (active_palette.max_iterations().get() as f64).powi(2) as i32;
-            mandelbrot.palette.offset = active_palette.offset().get() as f32;
-            mandelbrot.palette.length = active_palette.length().get() as f32;
+            mandelbrot.palette.offset = offset as f32;
+            mandelbrot.palette.length = length as f32;
             if let Some(redraw) = &mandelbrot.redraw {
                 redraw();
             }
         }
     });
 
-    let set_gradient = move |value| {
-        let gradient = Gradient::from(value);
+    // color cycling bypasses the store, so that frames don't persist the palette or re-run its effects
+    let cycle = StoredValue::new((0.0, 1.0));
+    let cycling = RwSignal::new(selected_palette.get_untracked().cycle_speed != 0.0);
+    let animation = use_raf_fn_with_options(
+        {
+            let mandelbrot = context.mandelbrot.clone();
+            move |UseRafFnCallbackArgs { delta, .. }| {
+                let speed = active_palette.cycle_speed().get_untracked();
+                let ping_pong = active_palette.cycle_ping_pong().get_untracked();
+                let offset = cycle.with_value(|&(offset, direction)| offset + direction * speed * delta / 1000.0);
+                let (offset, direction) = match (ping_pong, offset) {
+                    (true, offset) if offset > 1.0 => (2.0 - offset, -cycle.get_value().1),
+                    (true, offset) if offset < 0.0 => (-offset, -cycle.get_value().1),
+                    (true, offset) => (offset, cycle.get_value().1),
+                    (false, offset) => (offset.rem_euclid(1.0), 1.0),
+                };
+                cycle.set_value((offset, direction));
+
+                let mut mandelbrot = mandelbrot.lock().unwrap();
+                mandelbrot.palette.offset = offset as f32;
+                if let Some(redraw) = &mandelbrot.redraw {
+                    redraw();
+                }
+            }
+        },
+        UseRafFnOptions::default().immediate(false),
+    );
+
+    Effect::new({
+        let resume = animation.resume.clone();
+        let pause = animation.pause.clone();
+        move |was_cycling: Option<bool>| {
+            let is_cycling = cycling.get();
+            if is_cycling {
+                cycle.set_value((active_palette.offset().get_untracked(), 1.0));
+                resume();
+            } else if was_cycling == Some(true) {
+                pause();
+                // keep the offset the animation stopped at
+                active_palette.offset().set(cycle.get_value().0);
+            }
+            is_cycling
+        }
+    });
+
+    let set_gradient = move |gradient: Gradient| {
         {
             let mut mandelbrot = context.mandelbrot.lock().unwrap();
             mandelbrot.palette.gradient = gradient.clone().into();
@@ -77,13 +362,48 @@ pub fn Visuals(palette: RwSignal<Palette>, on_update: impl Fn(Palette) + 'static
         }
     };
 
+    let select_gradient_type = move |gradient: Gradient| {
+        selected_palette.set(Palette {
+            gradient,
+            ..active_palette.get_untracked()
+        })
+    };
+    let is_wave = move || matches!(active_palette.gradient().get(), Gradient::Wave(_));
+
     view! {
         <div class="flex flex-col">
+            <div class="flex flex-row gap-2 mb-2">
+                <button
+                    on:click=move |_| if is_wave() { select_gradient_type(StepGradient::default().into()) }
+                    class=move || if is_wave() {
+                        "px-3 py-1 bg-gray-700 hover:bg-gray-600 rounded-md text-white text-sm font-medium transition"
+                    } else {
+                        "px-3 py-1 bg-blue-600 rounded-md text-white text-sm font-medium"
+                    }
+                >
+                    "Step"
+                </button>
+                <button
+                    on:click=move |_| if !is_wave() { select_gradient_type(WaveGradient::default().into()) }
+                    class=move || if is_wave() {
+                        "px-3 py-1 bg-blue-600 rounded-md text-white text-sm font-medium"
+                    } else {
+                        "px-3 py-1 bg-gray-700 hover:bg-gray-600 rounded-md text-white text-sm font-medium transition"
+                    }
+                >
+                    "Wave"
+                </button>
+            </div>
             {
                 move || {
+                    let set_gradient = set_gradient.clone();
                     match selected_palette.get().gradient {
-                        Gradient::Wave(_) => view! { <wave::Wave/> }.into_any(),
-                        Gradient::Step(gradient) => view! { <step::Editor gradient on_update=set_gradient.clone()/> }.into_any(),
+                        Gradient::Wave(gradient) => view! {
+                            <wave::Editor gradient on_update=move |gradient| set_gradient(gradient.into())/>
+                        }.into_any(),
+                        Gradient::Step(gradient) => view! {
+                            <step::Editor gradient on_update=move |gradient| set_gradient(gradient.into())/>
+                        }.into_any(),
                     }
                 }
             }
@@ -91,27 +411,95 @@ pub fn Visuals(palette: RwSignal<Palette>, on_update: impl Fn(Palette) + 'static
             <div class="space-y-2">
                 <div class="flex justify-between items-center">
                     <label class="text-sm font-medium text-gray-300">"Max iterations"</label>
+                    <span class="text-sm font-mono text-accent2">{move || effective_iterations.get()}</span>
+                </div>
+                <label class="flex items-center gap-2 text-sm text-gray-300">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || active_palette.auto_iterations().enabled().get()
+                        on:change=move |ev| active_palette.auto_iterations().enabled().set(event_target_checked(&ev))
+                        class="accent-accent1 w-4 h-4"
+                    />
+                    "Scale with zoom depth"
+                </label>
+                <Show
+                    when=move || active_palette.auto_iterations().enabled().get()
+                    fallback=move || view! {
+                        <Slider
+                            max=200.0
+                            value=active_palette.max_iterations()
+                            class="w-full bg-gray-300 rounded-full focus:outline-none"
+                        />
+                    }
+                >
+                    <div class="flex justify-between items-center">
+                        <label class="text-xs text-gray-300">"Multiplier"</label>
+                        <span class="text-xs font-mono text-accent2">
+                            {move || format!("{:.2}×", active_palette.auto_iterations().multiplier().get())}
+                        </span>
+                    </div>
+                    <Slider
+                        min=0.1
+                        max=5.0
+                        value=active_palette.auto_iterations().multiplier()
+                        class="w-full bg-gray-300 rounded-full focus:outline-none"
+                    />
+                    <div class="flex flex-row gap-4">
+                        <IterationBound label="Minimum" value=active_palette.auto_iterations().min()/>
+                        <IterationBound label="Maximum" value=active_palette.auto_iterations().max()/>
+                    </div>
+                </Show>
+            </div>
+
+            <div class="space-y-2">
+                <div class="flex justify-between items-center">
+                    <label class="text-sm font-medium text-gray-300">"Color offset"</label>
                     <span class="text-sm font-mono text-accent2">
-                        {move || format!("{:.0}", active_palette.max_iterations().get().powi(2))}
+                        {move || format!("{:.4}", active_palette.offset().get())}
                     </span>
                 </div>
                 <Slider
-                    max=200.0
-                    value=active_palette.max_iterations()
+                    max=1.0
+                    value=active_palette.offset()
                     class="w-full bg-gray-300 rounded-full focus:outline-none"
                 />
             </div>
 
             <div class="space-y-2">
                 <div class="flex justify-between items-center">
-                    <label class="text-sm font-medium text-gray-300">"Color offset"</label>
+                    <label class="text-sm font-medium text-gray-300">"Color cycling"</label>
                     <span class="text-sm font-mono text-accent2">
-                        {move || format!("{:.4}", active_palette.offset().get())}
+                        {move || format!("{:.3}/s", active_palette.cycle_speed().get())}
                     </span>
                 </div>
+                <div class="flex flex-row items-center gap-2">
+                    <button
+                        on:click=move |_| cycling.update(|cycling| *cycling = !*cycling)
+                        class="px-3 py-1 bg-blue-600 hover:bg-blue-500 rounded-md text-white text-sm font-medium transition"
+                    >
+                        {move || if cycling.get() { "Pause" } else { "Play" }}
+                    </button>
+                    <button
+                        on:click=move |_| active_palette.cycle_speed().update(|speed| *speed = -*speed)
+                        class="px-3 py-1 bg-gray-700 hover:bg-gray-600 rounded-md text-white text-sm font-medium transition"
+                        title="Reverse direction"
+                    >
+                        "⇄"
+                    </button>
+                    <label class="flex items-center gap-2 text-sm text-gray-300">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || active_palette.cycle_ping_pong().get()
+                            on:change=move |ev| active_palette.cycle_ping_pong().set(event_target_checked(&ev))
+                            class="accent-accent1 w-4 h-4"
+                        />
+                        "Ping-pong"
+                    </label>
+                </div>
                 <Slider
-                    max=1.0
-                    value=active_palette.offset()
+                    min=-0.5
+                    max=0.5
+                    value=active_palette.cycle_speed()
                     class="w-full bg-gray-300 rounded-full focus:outline-none"
                 />
             </div>
@@ -132,61 +520,67 @@ pub fn Visuals(palette: RwSignal<Palette>, on_update: impl Fn(Palette) + 'static
 
             <details class="border-b w-full text-gray-700 rounded-md">
                 <summary class="cursor-pointer px-2 py-2 bg-gray-100 hover:bg-gray-200">
-                    Saved Palettes
+                    Presets
                 </summary>
-                <div class="px-4 py-2 bg-gray-100 shadow-sm">
-                    <div class="py-2">
-                        <button
-                            on:click={
-                                move |_| {
-                                    palettes.update(|palettes| { palettes.push(active_palette.get()); });
-                                    store_palettes();
-                                }
-                            }
-                            class="px-4 py-2 bg-green-600 hover:bg-green-500 rounded-md text-sm font-semibold transition"
-                        >Save</button>
-                    </div>
-                    <For
-                        each=move || palettes.get().into_iter().enumerate()
-                        key=|(_, palette)| palette.key()
-                        let((i, palette))
-                    >
-                        {
-                            match palette.gradient.clone() {
-                                Gradient::Wave(_) => todo!(),
-                                Gradient::Step(gradient) => view! {
-                                    <step::Bar
-                                        position=RwSignal::new(0.0).write_only()
-                                        width=RwSignal::new(0.0).write_only()
-                                        points=Store::new(gradient.into())
-                                        on_click=|_| {}
-                                        length=palette.length
-                                        offset=palette.offset
-                                    >
-                                        <div class="w-full flex flex-row items-center justify-end gap-2 p-4">
-                                            <button
-                                                on:click=move |_| selected_palette.set(palette.clone())
-                                                class="px-3 py-1 bg-blue-600 hover:bg-blue-500 rounded-md text-white text-sm font-medium transition"
-                                            >
-                                                Load
-                                            </button>
-                                            <button
-                                                on:click=move |_| {
-                                                    palettes.update(|palettes| { palettes.remove(i); });
-                                                    store_palettes();
+                <div class="px-4 py-2 bg-gray-100 shadow-sm space-y-2">
+                    {presets().into_iter().map(|preset| {
+                        let name = preset.name;
+                        let palette = Palette::from(preset);
+                        view! {
+                            <Preview gradient=palette.gradient.clone() length=palette.length offset=palette.offset>
+                                <div class="w-full flex flex-row items-center justify-between gap-2 p-4">
+                                    <span class="px-2 rounded bg-gray-900/60 text-sm font-semibold text-white">{name}</span>
+                                    <button
+                                        on:click=move |_| {
+                                            // applying a preset forks it, so that editing never changes the preset, an
+                                            // unedited fork is reused
+                                            palettes.update(|palettes| {
+                                                if !palettes.iter().any(|saved| saved.palette == palette) {
+                                                    library::add(palettes, name.to_string(), palette.clone());
                                                 }
-                                                class="px-3 py-1 bg-red-600 hover:bg-red-500 rounded-md text-white text-sm font-medium transition"
-                                            >
-                                                Delete
-                                            </button>
-                                        </div>
-                                    </step::Bar>
-                                }.into_any(),
-                            }
+                                            });
+                                            selected_palette.set(palette.clone());
+                                        }
+                                        class="px-3 py-1 bg-blue-600 hover:bg-blue-500 rounded-md text-white text-sm font-medium transition"
+                                    >
+                                        Apply
+                                    </button>
+                                </div>
+                            </Preview>
                         }
-                    </For>
+                    }).collect_view()}
                 </div>
             </details>
+
+            <details class="border-b w-full text-gray-700 rounded-md">
+                <summary class="cursor-pointer px-2 py-2 bg-gray-100 hover:bg-gray-200">
+                    Saved Palettes
+                </summary>
+                <Library palettes active_palette selected_palette/>
+            </details>
         </div>
     }
 }
+
+#[component]
+fn IterationBound(
+    label: &'static str,
+    value: impl Get<Value = f64> + Set<Value = f64> + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    view! {
+        <label class="flex items-center gap-2 text-xs text-gray-300">
+            {label}
+            <input
+                type="number"
+                min="1"
+                prop:value=move || value.get().to_string()
+                on:change=move |ev| {
+                    if let Ok(bound) = event_target_value(&ev).parse::<f64>() {
+                        value.set(bound.max(1.0));
+                    }
+                }
+                class="w-24 px-2 py-1 rounded bg-gray-800 border border-gray-600 font-mono text-white"
+            />
+        </label>
+    }
+}
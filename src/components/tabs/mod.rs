@@ -1,26 +1,31 @@
 mod about;
+mod address_book;
 mod auction;
 mod explorer;
 mod guide;
 mod inventory;
 mod sales;
+mod watchlist;
 
 use leptos::prelude::*;
 use leptos_ethereum_provider::EthereumInterface;
 use leptos_router::{hooks::use_params, params::Params};
 use send_wrapper::SendWrapper;
 
-use crate::context::{Context, StateStoreFields};
+use crate::context::{Context, StateStoreFields, Tab};
 
-use {about::About, auction::Auction, explorer::Explorer, guide::Guide, inventory::Inventory, sales::Sales};
+use {
+    about::About, address_book::AddressBook, auction::Auction, explorer::Explorer, guide::Guide, inventory::Inventory,
+    sales::Sales, watchlist::Watchlist,
+};
 
 #[derive(Clone, Params, PartialEq)]
 struct ControllerParams {
     token_id: Option<u128>,
 }
 
-fn tab_class(tab_name: &str, selected_tab: &str) -> String {
-    if tab_name == selected_tab {
+fn tab_class(tab: Tab, selected_tab: Tab) -> String {
+    if tab == selected_tab {
         // Active tab styling
         "px-4 py-2 font-medium border-b-2 border-blue-600 text-blue-600".to_string()
     } else {
@@ -34,7 +39,12 @@ pub fn Tabs() -> impl IntoView {
     let ethereum = use_context::<Option<EthereumInterface>>().unwrap();
     let params = use_params::<ControllerParams>();
     let context = use_context::<SendWrapper<Context>>().unwrap();
-    let selected_tab = RwSignal::new("explorer");
+    let selected_tab = context.state.selected_tab();
+    let watchlist_changes = context.state.watchlist_changes();
+    let badge = move |tab| match tab {
+        Tab::Watchlist => watchlist_changes.with(Vec::len),
+        _ => 0,
+    };
 
     Effect::new({
         let context = context.clone();
@@ -51,21 +61,29 @@ pub fn Tabs() -> impl IntoView {
             {
                 move || {
                     vec![
-                        ("explorer", "Explore", true),
-                        ("auction", "Auction", ethereum.is_some()),
-                        ("inventory", "Inventory", ethereum.as_ref().is_some_and(|eth| eth.connected())),
-                        ("sales", "Sales", ethereum.as_ref().is_some_and(|eth| eth.connected())),
-                        ("description", "Description", true),
-                        ("how_to_use", "How to Use", true),
+                        (Tab::Explorer, "Explore", true),
+                        (Tab::Auction, "Auction", ethereum.is_some()),
+                        (Tab::Inventory, "Inventory", ethereum.as_ref().is_some_and(|eth| eth.connected())),
+                        (Tab::Sales, "Sales", ethereum.as_ref().is_some_and(|eth| eth.connected())),
+                        (Tab::Watchlist, "Watchlist", true),
+                        (Tab::AddressBook, "Address Book", true),
+                        (Tab::Description, "Description", true),
+                        (Tab::HowToUse, "How to Use", true),
                     ]
                         .into_iter()
-                        .filter_map(|(name, label, show)| {
+                        .filter_map(|(tab, label, show)| {
                             show.then(|| view! {
                                 <button
-                                    class=move || tab_class(name, selected_tab.get())
-                                    on:click=move |_| selected_tab.set(name)
+                                    class=move || tab_class(tab, selected_tab.get())
+                                    on:click=move |_| selected_tab.set(tab)
                                 >
                                     {label}
+                                    {move || {
+                                        let count = badge(tab);
+                                        (count > 0).then(|| view! {
+                                            <span class="ml-1 px-1.5 rounded-full bg-red-600 text-white text-xs">{count}</span>
+                                        })
+                                    }}
                                 </button>
                             })
                         })
@@ -76,22 +94,28 @@ pub fn Tabs() -> impl IntoView {
 
         <div class="w-full mx-auto overflow-y-auto max-h-[84vh] scroll-smooth">
             <div class="p-4 space-y-4">
-                <div class=move || if selected_tab.get() == "explorer" { "block" } else { "hidden" }>
+                <div class=move || if selected_tab.get() == Tab::Explorer { "block" } else { "hidden" }>
                     <Explorer/>
                 </div>
-                <div class=move || if selected_tab.get() == "auction" { "block" } else { "hidden" }>
+                <div class=move || if selected_tab.get() == Tab::Auction { "block" } else { "hidden" }>
                     <Auction/>
                 </div>
-                <div class=move || if selected_tab.get() == "inventory" { "block" } else { "hidden" }>
+                <div class=move || if selected_tab.get() == Tab::Inventory { "block" } else { "hidden" }>
                     <Inventory />
                 </div>
-                <div class=move || if selected_tab.get() == "sales" { "block" } else { "hidden" }>
+                <div class=move || if selected_tab.get() == Tab::Sales { "block" } else { "hidden" }>
                     <Sales />
                 </div>
-                <div class=move || if selected_tab.get() == "description" { "block" } else { "hidden" }>
+                <div class=move || if selected_tab.get() == Tab::Watchlist { "block" } else { "hidden" }>
+                    <Watchlist />
+                </div>
+                <div class=move || if selected_tab.get() == Tab::AddressBook { "block" } else { "hidden" }>
+                    <AddressBook />
+                </div>
+                <div class=move || if selected_tab.get() == Tab::Description { "block" } else { "hidden" }>
                     <About />
                 </div>
-                <div class=move || if selected_tab.get() == "how_to_use" { "block" } else { "hidden" }>
+                <div class=move || if selected_tab.get() == Tab::HowToUse { "block" } else { "hidden" }>
                     <Guide />
                 </div>
             </div>
@@ -6,7 +6,8 @@ use mandelbrot_explorer::FrameColor;
 use send_wrapper::SendWrapper;
 
 use crate::{
-    context::{Context, ExplorerStoreFields, InventoryStoreFields, SalesStoreFields, StateStoreFields},
+    components::primitive::AddressTag,
+    context::{Context, InventoryStoreFields, SalesStoreFields, StateStoreFields},
     util::preserve_log_level,
 };
 
@@ -60,20 +61,7 @@ pub fn Sales() -> impl IntoView {
 
     let toggle_bid = {
         let context = context.clone();
-        move |token_id, bid_id, selected| {
-            context.state.sales().bids().update(|bids| {
-                if let Some(bids) = bids.get_mut(&token_id) {
-                    if let Some(bid) = bids.get_mut(&bid_id) {
-                        bid.selected = selected;
-                    }
-                }
-            });
-            context.state.explorer().bids().update(|bids| {
-                if let Some(bid) = bids.get_mut(&bid_id) {
-                    bid.selected = selected;
-                }
-            });
-        }
+        move |bid_id, selected| context.select_bid(bid_id, selected)
     };
 
     let selected_bids = Signal::derive({
@@ -93,6 +81,11 @@ pub fn Sales() -> impl IntoView {
 
     let total_approve_amount = move || 0f64.max(selected_bids.get().iter().map(|bid| bid.locked_tokens).sum::<f64>());
 
+    let clear_selection = {
+        let context = context.clone();
+        move || context.clear_selection()
+    };
+
     let approve_bids = Action::new_local({
         let context = context.clone();
         move |_| {
@@ -171,19 +164,16 @@ pub fn Sales() -> impl IntoView {
                                                                                 on:change={
                                                                                     let toggle_bid = toggle_bid.clone();
                                                                                     move |ev| {
-                                                                                        toggle_bid(
-                                                                                            token.token_id,
-                                                                                            bid.token_id,
-                                                                                            event_target_checked(&ev),
-                                                                                        );
+                                                                                        toggle_bid(bid.token_id, event_target_checked(&ev));
                                                                                     }
                                                                                 }
                                                                                 class="accent-accent1 w-4 h-4"
                                                                             />
                                                                         }
                                                                     }
-                                                                    <span class="text-sm font-mono">
-                                                                        {format!("{} {:?}", bid.locked_tokens.to_string(), bid.owner)}
+                                                                    <span class="flex items-center gap-2 text-sm font-mono">
+                                                                        {bid.locked_tokens.to_string()}
+                                                                        <AddressTag address=bid.owner/>
                                                                     </span>
                                                                 </div>
                                                                 <button
@@ -213,12 +203,20 @@ pub fn Sales() -> impl IntoView {
                     <span class="text-sm font-mono">{move || total_approve_amount()}</span>
                 </div>
 
-                <button
-                    on:click=move |_| { approve_bids.dispatch(()); }
-                    class="px-4 py-2 bg-green-600 hover:bg-green-500 rounded-md text-sm font-semibold transition"
-                >
-                    "Approve"
-                </button>
+                <div class="flex items-center gap-2">
+                    <button
+                        on:click=move |_| clear_selection()
+                        class="px-4 py-2 bg-gray-700 hover:bg-gray-600 rounded-md text-sm font-semibold transition"
+                    >
+                        "Clear selection"
+                    </button>
+                    <button
+                        on:click=move |_| { approve_bids.dispatch(()); }
+                        class="px-4 py-2 bg-green-600 hover:bg-green-500 rounded-md text-sm font-semibold transition"
+                    >
+                        "Approve"
+                    </button>
+                </div>
             </div>
 
             // <!-- Refresh Button -->
@@ -0,0 +1,198 @@
+use leptos::{prelude::*, task::spawn_local};
+use leptos_router::hooks::{use_navigate, use_query_map};
+use mandelbrot_explorer::FrameColor;
+use send_wrapper::SendWrapper;
+use web3::types::Address;
+
+use crate::{
+    components::primitive::AddressTag,
+    context::{Context, StateStoreFields},
+    util::preserve_log_level,
+};
+
+#[component]
+fn Changes(descriptions: Vec<String>) -> impl IntoView {
+    view! {
+        <ul class="list-disc list-inside text-xs text-accent2">
+            {descriptions.into_iter().map(|description| view! { <li>{description}</li> }).collect_view()}
+        </ul>
+    }
+}
+
+#[component]
+pub fn Watchlist() -> impl IntoView {
+    let context = use_context::<SendWrapper<Context>>().unwrap();
+    let navigate = use_navigate();
+    let query_map = use_query_map();
+    let watchlist = context.state.watchlist();
+    let changes = context.state.watchlist_changes();
+    let new_token_id = RwSignal::new(String::new());
+    let new_address = RwSignal::new(String::new());
+
+    let refresh = Action::new_local({
+        let context = context.clone();
+        move |_| {
+            let context = context.clone();
+            async move {
+                context.reload_watchlist().await;
+            }
+        }
+    });
+
+    let open_token = {
+        let context = context.clone();
+        move |token_id: u128| {
+            navigate(
+                &preserve_log_level(format!("/tokens/{}", token_id), query_map),
+                Default::default(),
+            );
+            let context = context.clone();
+            spawn_local(async move {
+                if let Ok(token) = context.contract.get_metadata(token_id).await {
+                    let frame = token.to_frame(FrameColor::Blue);
+                    context.mandelbrot.lock().unwrap().move_into_bounds(&frame.bounds);
+                }
+            });
+        }
+    };
+
+    let add_token = move |_| {
+        if let Ok(token_id) = new_token_id.get_untracked().trim().parse::<u128>() {
+            watchlist.update(|watchlist| {
+                watchlist.tokens.insert(token_id);
+            });
+            new_token_id.set(String::new());
+        }
+    };
+
+    let add_address = move |_| {
+        if let Ok(address) = new_address
+            .get_untracked()
+            .trim()
+            .trim_start_matches("0x")
+            .parse::<Address>()
+        {
+            watchlist.update(|watchlist| {
+                watchlist.addresses.insert(address);
+            });
+            new_address.set(String::new());
+        }
+    };
+
+    view! {
+        <div class="space-y-4">
+            <div class="flex flex-row gap-2 items-center">
+                <input
+                    type="number"
+                    min="1"
+                    placeholder="NFT id"
+                    class="w-32 px-2 py-1 rounded-md border border-gray-300 text-sm"
+                    prop:value=move || new_token_id.get()
+                    on:input=move |ev| new_token_id.set(event_target_value(&ev))
+                />
+                <button
+                    on:click=add_token
+                    class="px-4 py-2 bg-green-600 hover:bg-green-500 rounded-md text-white text-sm font-semibold transition"
+                >
+                    "Watch NFT"
+                </button>
+            </div>
+            <div class="flex flex-row gap-2 items-center">
+                <input
+                    type="text"
+                    placeholder="0x..."
+                    class="flex-1 px-2 py-1 rounded-md border border-gray-300 font-mono text-sm"
+                    prop:value=move || new_address.get()
+                    on:input=move |ev| new_address.set(event_target_value(&ev))
+                />
+                <button
+                    on:click=add_address
+                    class="px-4 py-2 bg-green-600 hover:bg-green-500 rounded-md text-white text-sm font-semibold transition"
+                >
+                    "Watch address"
+                </button>
+            </div>
+
+            <div class="p-4 bg-gray-900 rounded-md space-y-2">
+                <For
+                    each=move || watchlist.get().tokens.into_iter()
+                    key=|token_id| *token_id
+                    let(token_id)
+                >
+                    <div class="flex items-start justify-between gap-4 bg-gray-800 text-white rounded px-4 py-2">
+                        <div class="flex flex-col text-sm">
+                            <span class="font-semibold">"NFT " {token_id}</span>
+                            {move || view! {
+                                <Changes descriptions=changes.with(|changes| {
+                                    changes
+                                        .iter()
+                                        .filter(|change| change.token_id() == Some(token_id))
+                                        .map(|change| change.description())
+                                        .collect()
+                                })/>
+                            }}
+                        </div>
+                        <div class="flex flex-wrap gap-2">
+                            <button
+                                on:click={
+                                    let open_token = open_token.clone();
+                                    move |_| open_token(token_id)
+                                }
+                                class="px-3 py-1 bg-blue-600 hover:bg-blue-500 rounded-md text-white text-sm font-medium transition"
+                            >
+                                "Zoom"
+                            </button>
+                            <button
+                                on:click=move |_| watchlist.update(|watchlist| { watchlist.tokens.remove(&token_id); })
+                                class="px-3 py-1 bg-red-600 hover:bg-red-500 rounded-md text-white text-sm font-medium transition"
+                            >
+                                "Unwatch"
+                            </button>
+                        </div>
+                    </div>
+                </For>
+                <For
+                    each=move || watchlist.get().addresses.into_iter()
+                    key=|address| *address
+                    let(address)
+                >
+                    <div class="flex items-start justify-between gap-4 bg-gray-800 text-white rounded px-4 py-2">
+                        <div class="flex flex-col text-sm">
+                            <AddressTag address/>
+                            {move || view! {
+                                <Changes descriptions=changes.with(|changes| {
+                                    changes
+                                        .iter()
+                                        .filter(|change| change.address() == Some(address))
+                                        .map(|change| change.description())
+                                        .collect()
+                                })/>
+                            }}
+                        </div>
+                        <button
+                            on:click=move |_| watchlist.update(|watchlist| { watchlist.addresses.remove(&address); })
+                            class="px-3 py-1 bg-red-600 hover:bg-red-500 rounded-md text-white text-sm font-medium transition"
+                        >
+                            "Unwatch"
+                        </button>
+                    </div>
+                </For>
+            </div>
+
+            <div class="flex flex-row gap-2">
+                <button
+                    on:click=move |_| { refresh.dispatch(()); }
+                    class="px-4 py-2 bg-gray-700 hover:bg-gray-600 text-white rounded-md text-sm font-semibold transition"
+                >
+                    "Refresh"
+                </button>
+                <button
+                    on:click=move |_| context.mark_watchlist_seen()
+                    class="px-4 py-2 bg-blue-600 hover:bg-blue-500 text-white rounded-md text-sm font-semibold transition"
+                >
+                    "Mark as seen"
+                </button>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,176 @@
+use std::time::Duration;
+
+use leptos::{ev, prelude::*};
+use leptos_use::{UseEventListenerOptions, use_event_listener_with_options, use_window};
+use mandelbrot_explorer::{Bounds, FrameEvent, ISample};
+use send_wrapper::SendWrapper;
+use wasm_bindgen::JsCast;
+
+use super::frame_selection::canvas_position;
+use crate::{
+    context::Context,
+    coordinates::{contains, pinch, plane_point},
+};
+
+const LONG_PRESS_DELAY: Duration = Duration::from_millis(500);
+// distance in pixels a finger may drift before a press stops counting as long
+const LONG_PRESS_TOLERANCE: f64 = 10.0;
+
+struct Pinch {
+    bounds: Bounds,
+    midpoint: (f64, f64),
+    distance: f64,
+}
+
+#[derive(Default)]
+struct State {
+    // touches on the canvas as pointer id and client position
+    touches: Vec<(i32, (f64, f64))>,
+    pinch: Option<Pinch>,
+    long_press: Option<(TimeoutHandle, (f64, f64))>,
+}
+
+impl State {
+    fn cancel_long_press(&mut self) {
+        if let Some((long_press, _)) = self.long_press.take() {
+            long_press.clear();
+        }
+    }
+
+    /// Midpoint in client pixels and distance between the first two touches.
+    fn span(&self) -> Option<((f64, f64), f64)> {
+        let [(_, a), (_, b), ..] = self.touches.as_slice() else {
+            return None;
+        };
+        Some((((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0), (a.0 - b.0).hypot(a.1 - b.1)))
+    }
+}
+
+/// Touch gestures on the canvas: pinch to zoom and drag two fingers to pan, long-press on a frame acts as a double
+/// click. Single finger drags are left to the explorer.
+#[component]
+pub fn TouchControl() -> impl IntoView {
+    let context = use_context::<SendWrapper<Context>>().unwrap();
+    let canvas = use_context::<NodeRef<leptos::html::Canvas>>().unwrap();
+    let state = StoredValue::new_local(State::default());
+
+    let is_canvas_touch = move |ev: &web_sys::PointerEvent| {
+        let target = ev
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::HtmlCanvasElement>().ok());
+        ev.pointer_type() == "touch" && target.is_some() && target == canvas.get_untracked()
+    };
+
+    let long_press = {
+        let context = context.clone();
+        move |position: (f64, f64)| {
+            state.update_value(|state| state.long_press = None);
+            let Some(canvas) = canvas.get_untracked() else {
+                return;
+            };
+            let Some((x, y)) = canvas_position(&canvas, position.0, position.1) else {
+                return;
+            };
+            let (frame, on_frame_event) = {
+                let mandelbrot = context.mandelbrot.lock().unwrap();
+                let bounds = mandelbrot.engine.borrow().get_bounds();
+                let (x, y) = plane_point(&bounds, x, y);
+                (
+                    mandelbrot
+                        .frames
+                        .iter()
+                        .find(|frame| contains(&frame.bounds, &x, &y))
+                        .cloned(),
+                    mandelbrot.on_frame_event.clone(),
+                )
+            };
+            if let Some((frame, on_frame_event)) = frame.zip(on_frame_event) {
+                on_frame_event(FrameEvent::DoubleClicked(frame));
+            }
+        }
+    };
+
+    // Listeners are registered on the window in the capture phase, so that the explorer doesn't also pan while
+    // pinching.
+    let options = UseEventListenerOptions::default().capture(true);
+    let _ = use_event_listener_with_options(
+        use_window(),
+        ev::pointerdown,
+        {
+            let context = context.clone();
+            move |ev| {
+                if !is_canvas_touch(&ev) {
+                    return;
+                }
+                let position = (ev.client_x() as f64, ev.client_y() as f64);
+                state.update_value(|state| {
+                    state.touches.push((ev.pointer_id(), position));
+                    state.cancel_long_press();
+                    match state.touches.len() {
+                        1 => {
+                            let long_press = long_press.clone();
+                            state.long_press = set_timeout_with_handle(move || long_press(position), LONG_PRESS_DELAY)
+                                .ok()
+                                .map(|handle| (handle, position));
+                        }
+                        2 => {
+                            ev.stop_propagation();
+                            let (Some(canvas), Some((midpoint, distance))) = (canvas.get_untracked(), state.span())
+                            else {
+                                return;
+                            };
+                            state.pinch = canvas_position(&canvas, midpoint.0, midpoint.1).map(|midpoint| Pinch {
+                                bounds: context.mandelbrot.lock().unwrap().engine.borrow().get_bounds(),
+                                midpoint,
+                                distance,
+                            });
+                        }
+                        _ => ev.stop_propagation(),
+                    }
+                });
+            }
+        },
+        options.clone(),
+    );
+    let _ = use_event_listener_with_options(
+        use_window(),
+        ev::pointermove,
+        {
+            let context = context.clone();
+            move |ev| {
+                let position = (ev.client_x() as f64, ev.client_y() as f64);
+                let target = state.try_update_value(|state| {
+                    let touch = state.touches.iter_mut().find(|(id, _)| *id == ev.pointer_id())?;
+                    touch.1 = position;
+                    if state.long_press.as_ref().is_some_and(|(_, start)| {
+                        (position.0 - start.0).hypot(position.1 - start.1) > LONG_PRESS_TOLERANCE
+                    }) {
+                        state.cancel_long_press();
+                    }
+                    let gesture = state.pinch.as_ref()?;
+                    ev.stop_propagation();
+                    ev.prevent_default();
+                    let (midpoint, distance) = state.span()?;
+                    let midpoint = canvas_position(&canvas.get_untracked()?, midpoint.0, midpoint.1)?;
+                    (distance > 0.0)
+                        .then(|| pinch(&gesture.bounds, gesture.midpoint, midpoint, gesture.distance / distance))
+                });
+                if let Some(bounds) = target.flatten() {
+                    context.mandelbrot.lock().unwrap().move_into_bounds(&bounds);
+                }
+            }
+        },
+        options.clone(),
+    );
+    let release = move |ev: web_sys::PointerEvent| {
+        state.update_value(|state| {
+            state.touches.retain(|(id, _)| *id != ev.pointer_id());
+            state.cancel_long_press();
+            if state.touches.len() < 2 {
+                state.pinch = None;
+            }
+        });
+    };
+    let _ = use_event_listener_with_options(use_window(), ev::pointerup, release, options.clone());
+    let _ = use_event_listener_with_options(use_window(), ev::pointercancel, release, options);
+}
@@ -5,8 +5,15 @@ use std::{
 
 use leptos::prelude::*;
 use reactive_stores::Store;
+use serde::{Deserialize, Serialize};
 
-use crate::evm::{contracts::MandelbrotNFTContract, types::Metadata};
+use crate::{
+    address_book::AddressBook,
+    evm::{contracts::MandelbrotNFTContract, types::Metadata},
+    notifications::Notification,
+    util::{load_item, store_item},
+    watchlist::{AddressSnapshot, Change, Snapshot, TokenSnapshot, Watchlist},
+};
 
 #[derive(Clone, Default, Store)]
 pub struct Explorer {
@@ -26,10 +33,124 @@ pub struct Sales {
     pub bids: HashMap<u128, HashMap<u128, Metadata>>,
 }
 
+#[derive(Clone, Deserialize, PartialEq, Serialize, Store)]
+#[serde(default)]
+pub struct FrameFilters {
+    pub ancestry: bool,
+    pub others_children: bool,
+    pub own_tokens: bool,
+    pub others_bids: bool,
+    pub own_bids: bool,
+    pub selected_bids: bool,
+}
+
+impl Default for FrameFilters {
+    fn default() -> Self {
+        Self {
+            ancestry: true,
+            others_children: true,
+            own_tokens: true,
+            others_bids: true,
+            own_bids: true,
+            selected_bids: true,
+        }
+    }
+}
+
+impl FrameFilters {
+    pub fn show_token(&self, token: &Metadata) -> bool {
+        if token.owned {
+            self.own_tokens
+        } else {
+            self.others_children
+        }
+    }
+
+    pub fn show_bid(&self, bid: &Metadata) -> bool {
+        if bid.selected {
+            self.selected_bids
+        } else if bid.owned {
+            self.own_bids
+        } else {
+            self.others_bids
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, Deserialize, PartialEq, Serialize)]
+pub enum Engine {
+    #[default]
+    Perturbation,
+    Optimised,
+}
+
+impl Engine {
+    pub const ALL: [Self; 2] = [Self::Perturbation, Self::Optimised];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Perturbation => "Perturbation",
+            Self::Optimised => "Optimised",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Perturbation => "Follows a high precision reference orbit, for deep zooms",
+            Self::Optimised => "Iterates every pixel directly, faster at shallow zooms",
+        }
+    }
+}
+
+#[derive(Clone, Default, Deserialize, PartialEq, Serialize, Store)]
+pub struct RenderSettings {
+    pub engine: Engine,
+    /// Canvas pixels per CSS pixel, `None` to follow the device pixel ratio.
+    pub resolution_scale: Option<f64>,
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Tab {
+    #[default]
+    Explorer,
+    Auction,
+    Inventory,
+    Sales,
+    Watchlist,
+    AddressBook,
+    Description,
+    HowToUse,
+}
+
+impl Tab {
+    /// Tabs in the order they are shown.
+    pub const ALL: [Self; 8] = [
+        Self::Explorer,
+        Self::Auction,
+        Self::Inventory,
+        Self::Sales,
+        Self::Watchlist,
+        Self::AddressBook,
+        Self::Description,
+        Self::HowToUse,
+    ];
+}
+
 #[derive(Clone, Default, Store)]
 pub struct State {
     pub address: Option<web3::types::Address>,
     pub current_token_id: Option<u128>,
+    /// Focus of the view as written in links, updated whenever the view moves.
+    pub focus: String,
+    pub selected_tab: Tab,
+    pub frame_filters: FrameFilters,
+    pub address_book: AddressBook,
+    pub watchlist: Watchlist,
+    pub watchlist_snapshot: Option<Snapshot>,
+    pub watchlist_changes: Vec<Change>,
+    pub notifications: Vec<Notification>,
+    pub browser_notifications: bool,
+    pub render_settings: RenderSettings,
     pub explorer: Explorer,
     pub inventory: Inventory,
     pub sales: Sales,
@@ -43,6 +164,41 @@ pub struct Context {
 }
 
 impl Context {
+    pub fn select_bid(&self, bid_id: u128, selected: bool) {
+        self.select_bids(&[bid_id], selected);
+    }
+
+    pub fn select_bids(&self, bid_ids: &[u128], selected: bool) {
+        self.state.explorer().bids().update(|bids| {
+            for bid_id in bid_ids {
+                if let Some(bid) = bids.get_mut(bid_id) {
+                    bid.selected = selected;
+                }
+            }
+        });
+        self.state.sales().bids().update(|bids| {
+            for token_bids in bids.values_mut() {
+                for bid_id in bid_ids {
+                    if let Some(bid) = token_bids.get_mut(bid_id) {
+                        bid.selected = selected;
+                    }
+                }
+            }
+        });
+    }
+
+    pub fn clear_selection(&self) {
+        self.state
+            .explorer()
+            .bids()
+            .update(|bids| bids.values_mut().for_each(|bid| bid.selected = false));
+        self.state.sales().bids().update(|bids| {
+            bids.values_mut()
+                .flat_map(|bids| bids.values_mut())
+                .for_each(|bid| bid.selected = false)
+        });
+    }
+
     pub async fn reload_inventory(&self) {
         if let Some(address) = self.state.address().get_untracked() {
             if let Ok((tokens, bids)) = self.contract.get_owned_items(address).await {
@@ -69,6 +225,7 @@ impl Context {
             .filter_map(|(&bid_id, &Metadata { selected, .. })| selected.then(|| bid_id))
             .collect::<Vec<_>>();
 
+        let previous_bids = self.state.sales().bids().get_untracked();
         let bids = futures::future::join_all(
             self.state
                 .inventory()
@@ -90,11 +247,54 @@ impl Context {
                         })
                         .collect::<HashMap<_, _>>()
                 } else {
-                    HashMap::new()
+                    previous_bids.get(&token_id).cloned().unwrap_or_default()
                 },
             )
         })
         .collect::<HashMap<_, _>>();
         self.state.sales().bids().set(bids);
     }
+
+    /// Query the current state of watched tokens and addresses and compare it with the snapshot taken when the
+    /// watchlist was last marked as seen.
+    pub async fn reload_watchlist(&self) {
+        let watchlist = self.state.watchlist().get_untracked();
+        let tokens = futures::future::join_all(watchlist.tokens.iter().map(|&token_id| async move {
+            match (
+                self.contract.get_metadata(token_id).await,
+                self.contract.get_bids(token_id).await,
+                self.contract.get_children_metadata(token_id).await,
+            ) {
+                (Ok(token), Ok(bids), Ok(children)) => Some((token_id, TokenSnapshot::new(&token, &bids, &children))),
+                _ => None,
+            }
+        }))
+        .await;
+        let addresses = futures::future::join_all(watchlist.addresses.iter().map(|&address| async move {
+            self.contract
+                .get_owned_items(address)
+                .await
+                .ok()
+                .map(|(tokens, bids)| (address, AddressSnapshot::new(&tokens, &bids)))
+        }))
+        .await;
+        let current = Snapshot {
+            tokens: tokens.into_iter().flatten().collect(),
+            addresses: addresses.into_iter().flatten().collect(),
+        };
+
+        let mut seen = load_item::<Snapshot>("watchlist_seen").unwrap_or_default();
+        seen.extend_missing(&current);
+        store_item("watchlist_seen", &seen);
+
+        self.state.watchlist_changes().set(seen.diff(&current));
+        self.state.watchlist_snapshot().set(Some(current));
+    }
+
+    pub fn mark_watchlist_seen(&self) {
+        if let Some(current) = self.state.watchlist_snapshot().get_untracked() {
+            store_item("watchlist_seen", &current);
+            self.state.watchlist_changes().set(Vec::new());
+        }
+    }
 }
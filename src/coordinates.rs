@@ -0,0 +1,203 @@
+//! Conversions between `BigFloat` coordinates and the numbers shown to users.
+
+use mandelbrot_explorer::{BigFloat, Bounds, Radix};
+
+/// Width of the whole field of the root NFT, which is shown at a magnification of 1.
+pub const BASE_WIDTH: f64 = 3.0;
+
+pub fn to_f64(value: &BigFloat) -> f64 {
+    match value.convert_to_radix(Radix::Hex) {
+        Ok((sign, digits, exponent)) => {
+            // an f64 mantissa holds 53 bits, which 14 hex digits cover
+            let magnitude = digits
+                .iter()
+                .take(14)
+                .enumerate()
+                .fold(0.0, |magnitude, (index, digit)| {
+                    magnitude + *digit as f64 * 16f64.powi(exponent as i32 - 1 - index as i32)
+                });
+            if sign.is_positive() { magnitude } else { -magnitude }
+        }
+        Err(_) => f64::NAN,
+    }
+}
+
+/// Base 2 logarithm of a positive `value`, taken from its exponent so that it holds beyond the range of f64.
+pub fn log2(value: &BigFloat) -> f64 {
+    match value.convert_to_radix(Radix::Hex) {
+        Ok((_, digits, exponent)) => {
+            // the digits are the fraction of 16^exponent
+            let fraction = digits
+                .iter()
+                .take(14)
+                .enumerate()
+                .fold(0.0, |fraction, (index, digit)| {
+                    fraction + *digit as f64 * 16f64.powi(-1 - index as i32)
+                });
+            4.0 * exponent as f64 + fraction.log2()
+        }
+        Err(_) => f64::NAN,
+    }
+}
+
+/// 2 to the power of `exponent`, beyond the range of f64.
+pub fn pow2(exponent: f64) -> BigFloat {
+    let whole = exponent.floor();
+    let mut power = BigFloat::from(2f64.powf(exponent - whole));
+    let mut base = BigFloat::from(if whole < 0.0 { 0.5 } else { 2.0 });
+    let mut remaining = whole.abs() as u64;
+    while remaining > 0 {
+        if remaining & 1 == 1 {
+            power = power * base.clone();
+        }
+        base = base.clone() * base;
+        remaining >>= 1;
+    }
+    power
+}
+
+/// Every digit of `value` in plain decimal notation.
+pub fn to_decimal(value: &BigFloat) -> String {
+    let Ok((sign, digits, exponent)) = value.convert_to_radix(Radix::Dec) else {
+        return "NaN".to_string();
+    };
+    let Some(last) = digits.iter().rposition(|digit| *digit != 0) else {
+        return "0".to_string();
+    };
+    // the digits are the fraction of 10^exponent
+    let digits = digits[..=last]
+        .iter()
+        .map(|digit| (b'0' + digit) as char)
+        .collect::<String>();
+    let exponent = exponent as i64;
+    let magnitude = if exponent <= 0 {
+        format!("0.{}{}", "0".repeat(-exponent as usize), digits)
+    } else if exponent as usize >= digits.len() {
+        format!("{}{}", digits, "0".repeat(exponent as usize - digits.len()))
+    } else {
+        format!("{}.{}", &digits[..exponent as usize], &digits[exponent as usize..])
+    };
+    if sign.is_positive() {
+        magnitude
+    } else {
+        format!("-{}", magnitude)
+    }
+}
+
+/// Parse a decimal number such as `-0.75`, `1.5e-20` or `12`.
+pub fn parse_decimal(text: &str) -> Option<BigFloat> {
+    let text = text.trim();
+    let (mantissa, exponent) = match text.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+        None => (text, None),
+    };
+    let unsigned = mantissa.strip_prefix(['-', '+']).unwrap_or(mantissa);
+    let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    let valid_mantissa = !(integer.is_empty() && fraction.is_empty()) && is_digits(integer) && is_digits(fraction);
+    let valid_exponent = exponent.is_none_or(|exponent| {
+        let exponent = exponent.strip_prefix(['-', '+']).unwrap_or(exponent);
+        !exponent.is_empty() && is_digits(exponent)
+    });
+    (valid_mantissa && valid_exponent).then(|| BigFloat::parse(text, Radix::Dec))
+}
+
+/// Bounds of the root NFT, covering the whole set.
+pub fn whole_set() -> Bounds {
+    Bounds {
+        x_min: BigFloat::from(-2.1),
+        x_max: BigFloat::from(BASE_WIDTH - 2.1),
+        y_min: BigFloat::from(-BASE_WIDTH / 2.0),
+        y_max: BigFloat::from(BASE_WIDTH / 2.0),
+    }
+}
+
+pub fn center(bounds: &Bounds) -> (BigFloat, BigFloat) {
+    (
+        (&bounds.x_min + &bounds.x_max) * BigFloat::from(0.5),
+        (&bounds.y_min + &bounds.y_max) * BigFloat::from(0.5),
+    )
+}
+
+pub fn width(bounds: &Bounds) -> BigFloat {
+    &bounds.x_max - bounds.x_min.clone()
+}
+
+pub fn magnification(bounds: &Bounds) -> f64 {
+    BASE_WIDTH / to_f64(&width(bounds))
+}
+
+/// Magnification in scientific notation with `precision` decimals, e.g. `1.235e42`, also past the range of f64.
+pub fn format_magnification(bounds: &Bounds, precision: usize) -> String {
+    let log10 = (BASE_WIDTH.log2() - log2(&width(bounds))) * 2f64.log10();
+    let mut exponent = log10.floor();
+    let mut mantissa = format!("{:.*}", precision, 10f64.powf(log10 - exponent));
+    // rounding up to the next power of ten
+    if mantissa.starts_with("10") {
+        exponent += 1.0;
+        mantissa = format!("{:.*}", precision, 1.0);
+    }
+    format!("{}e{}", mantissa, exponent as i64)
+}
+
+/// Convert a position on the canvas, given as fractions of its width and height, into a point on the plane.
+pub fn plane_point(bounds: &Bounds, x: f64, y: f64) -> (BigFloat, BigFloat) {
+    (
+        &bounds.x_min + &(&bounds.x_max - bounds.x_min.clone()) * BigFloat::from(x),
+        &bounds.y_max - &(&bounds.y_max - bounds.y_min.clone()) * BigFloat::from(y),
+    )
+}
+
+/// Whether the point lies inside `bounds`, edges included.
+pub fn contains(bounds: &Bounds, x: &BigFloat, y: &BigFloat) -> bool {
+    &bounds.x_min <= x && x <= &bounds.x_max && &bounds.y_min <= y && y <= &bounds.y_max
+}
+
+/// Whether two bounds overlap, touching edges included.
+pub fn intersects(a: &Bounds, b: &Bounds) -> bool {
+    a.x_min <= b.x_max && b.x_min <= a.x_max && a.y_min <= b.y_max && b.y_min <= a.y_max
+}
+
+/// Bounds moved by fractions of their size, then scaled by `scale` around their center.
+pub fn pan_zoom(bounds: &Bounds, dx: f64, dy: f64, scale: f64) -> Bounds {
+    let (x, y) = center(bounds);
+    let (width, height) = (width(bounds), &bounds.y_max - bounds.y_min.clone());
+    let x = &x + &(width.clone() * BigFloat::from(dx));
+    let y = &y + &(height.clone() * BigFloat::from(dy));
+    let half_width = width * BigFloat::from(scale / 2.0);
+    let half_height = height * BigFloat::from(scale / 2.0);
+    Bounds {
+        x_min: &x - half_width.clone(),
+        x_max: &x + &half_width,
+        y_min: &y - half_height.clone(),
+        y_max: &y + &half_height,
+    }
+}
+
+/// Bounds scaled by `scale` so that the point at canvas fractions `from` ends up at `to`, as when pinching. Canvas
+/// fractions grow rightwards and downwards.
+pub fn pinch(bounds: &Bounds, from: (f64, f64), to: (f64, f64), scale: f64) -> Bounds {
+    let (width, height) = (width(bounds), &bounds.y_max - bounds.y_min.clone());
+    let x = &bounds.x_min + &(width.clone() * BigFloat::from(from.0));
+    let y = &bounds.y_max - height.clone() * BigFloat::from(from.1);
+    let (width, height) = (width * BigFloat::from(scale), height * BigFloat::from(scale));
+    let x_min = &x - width.clone() * BigFloat::from(to.0);
+    let y_max = &y + &(height.clone() * BigFloat::from(to.1));
+    Bounds {
+        x_max: &x_min + &width,
+        y_min: &y_max - height,
+        x_min,
+        y_max,
+    }
+}
+
+/// Square bounds around a center, `magnification` times smaller than the root field.
+pub fn bounds_around(x: &BigFloat, y: &BigFloat, magnification: BigFloat) -> Bounds {
+    let half_width = BigFloat::from(BASE_WIDTH / 2.0) / magnification;
+    Bounds {
+        x_min: x - half_width.clone(),
+        x_max: x + &half_width,
+        y_min: y - half_width.clone(),
+        y_max: y + &half_width,
+    }
+}
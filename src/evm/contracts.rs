@@ -1,14 +1,28 @@
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    ops::RangeInclusive,
+    sync::{Arc, Mutex},
+};
 
+use ethabi::{RawLog, token::Token};
 use eyre::Result;
+use futures::StreamExt;
 use web3::{
     Web3,
+    api::Eth,
     contract::{Contract, Options, tokens::Tokenize},
     transports::{Either, Http, eip_1193::Eip1193},
-    types::{Address, H256, TransactionReceipt, U256},
+    types::{Address, BlockId, BlockNumber, FilterBuilder, H256, Log, TransactionReceipt, U256},
 };
 
-use super::types::{Field, Metadata};
+use super::types::{Field, Metadata, MinimumPriceChange, NftTransfer};
+
+// Public RPC endpoints limit the block range of a single log query
+const LOG_BLOCK_RANGE: u64 = 50_000;
+// Log queries of one page of history, made one after another
+const LOG_PAGE_QUERIES: u64 = 4;
+// Requests in flight at once when loading block timestamps or past state
+const MAX_PARALLEL_REQUESTS: usize = 4;
 
 pub enum Error {
     TokenNotFound,
@@ -122,10 +136,33 @@ trait CallWrapper {
     }
 }
 
+/// Everything read from logs and past blocks, none of it changes once the blocks are final.
+#[derive(Default)]
+struct HistoryCache {
+    /// NFT transfers of `blocks`, oldest first.
+    transfers: Vec<NftTransfer>,
+    blocks: Option<RangeInclusive<u64>>,
+    timestamps: HashMap<u64, u64>,
+    /// Bid and child ids of a token at the end of a block.
+    relations: HashMap<(u128, u64), (Vec<u128>, Vec<u128>)>,
+    /// Minimum bid of a token at the end of a block.
+    minimum_prices: HashMap<(u128, u64), f64>,
+}
+
+/// Transfers of a range of blocks that ends at the latest block.
+pub struct NftHistory {
+    pub transfers: Vec<NftTransfer>,
+    pub blocks: RangeInclusive<u64>,
+    /// Whether the range starts at the deployment of the contract.
+    pub complete: bool,
+}
+
 #[derive(Clone)]
 pub struct MandelbrotNFTContract {
+    eth: Eth<Either<Eip1193, Http>>,
     contract: Contract<Either<Eip1193, Http>>,
     handle_error: Arc<dyn Fn(Error)>,
+    history: Arc<Mutex<HistoryCache>>,
 }
 
 impl CallWrapper for MandelbrotNFTContract {
@@ -141,6 +178,7 @@ impl CallWrapper for MandelbrotNFTContract {
 impl MandelbrotNFTContract {
     pub fn new(web3: &Web3<Either<Eip1193, Http>>, handle_error: Arc<dyn Fn(Error)>) -> Self {
         Self {
+            eth: web3.eth(),
             contract: Contract::from_json(
                 web3.eth(),
                 env!("CONTRACT_ADDRESS").trim_start_matches("0x").parse().unwrap(),
@@ -148,6 +186,7 @@ impl MandelbrotNFTContract {
             )
             .unwrap(),
             handle_error,
+            history: Default::default(),
         }
     }
 
@@ -169,7 +208,7 @@ impl MandelbrotNFTContract {
             .await
     }
 
-    pub async fn _get_metadata(&self, token_id: u128) -> Result<Metadata> {
+    pub async fn get_metadata(&self, token_id: u128) -> Result<Metadata> {
         let result: web3::contract::Result<Metadata> = self
             .contract
             .query("getMetadata", (U256::from(token_id),), None, Options::default(), None)
@@ -277,4 +316,345 @@ impl MandelbrotNFTContract {
         )
         .await
     }
+
+    /// NFT transfers from a page of recent blocks up to the latest block, oldest first. Blocks that were queried
+    /// before come from the cache, so refreshing only queries blocks mined since. With `older` the range is
+    /// extended by another page back in time, down to `DEPLOYMENT_BLOCK`.
+    pub async fn get_nft_history(&self, older: bool) -> Result<NftHistory> {
+        let deployment_block = option_env!("DEPLOYMENT_BLOCK")
+            .and_then(|block| block.parse::<u64>().ok())
+            .unwrap_or(0);
+        let page = LOG_BLOCK_RANGE * LOG_PAGE_QUERIES;
+        let latest_block = self.eth.block_number().await?.as_u64();
+        let cached = self.history.lock().unwrap().blocks.clone();
+
+        let (first_block, last_block) = match cached {
+            Some(blocks) => (*blocks.start(), *blocks.end()),
+            None => {
+                let first_block = latest_block.saturating_sub(page - 1).max(deployment_block);
+                let transfers = self.get_nft_transfers(first_block, latest_block).await?;
+                let mut history = self.history.lock().unwrap();
+                history.transfers = transfers;
+                history.blocks = Some(first_block..=latest_block);
+                (first_block, latest_block)
+            }
+        };
+        if last_block < latest_block {
+            let transfers = self.get_nft_transfers(last_block + 1, latest_block).await?;
+            let mut history = self.history.lock().unwrap();
+            history.transfers.extend(transfers);
+            history.blocks = Some(first_block..=latest_block);
+        }
+        let first_block = if older && first_block > deployment_block {
+            let older_block = first_block.saturating_sub(page).max(deployment_block);
+            let transfers = self.get_nft_transfers(older_block, first_block - 1).await?;
+            let mut history = self.history.lock().unwrap();
+            history.transfers.splice(0..0, transfers);
+            history.blocks = Some(older_block..=latest_block);
+            older_block
+        } else {
+            first_block
+        };
+
+        Ok(NftHistory {
+            transfers: self.history.lock().unwrap().transfers.clone(),
+            blocks: first_block..=latest_block,
+            complete: first_block <= deployment_block,
+        })
+    }
+
+    /// Query `TransferSingle` and `TransferBatch` logs one block window after another.
+    async fn get_nft_transfers(&self, from_block: u64, to_block: u64) -> Result<Vec<NftTransfer>> {
+        let abi = self.contract.abi();
+        let topics = vec![
+            abi.event("TransferSingle")?.signature(),
+            abi.event("TransferBatch")?.signature(),
+        ];
+        let mut transfers = Vec::new();
+        for window_start in (from_block..=to_block).step_by(LOG_BLOCK_RANGE as usize) {
+            let logs = self
+                .eth
+                .logs(
+                    FilterBuilder::default()
+                        .address(vec![self.contract.address()])
+                        .topics(Some(topics.clone()), None, None, None)
+                        .from_block(BlockNumber::Number(window_start.into()))
+                        .to_block(BlockNumber::Number(
+                            (window_start + LOG_BLOCK_RANGE - 1).min(to_block).into(),
+                        ))
+                        .build(),
+                )
+                .await?;
+            transfers.extend(logs.iter().flat_map(|log| self.decode_transfers(log)));
+        }
+        Ok(transfers)
+    }
+
+    /// Timestamps of blocks in seconds, blocks that can't be loaded are left out.
+    pub async fn get_block_timestamps(&self, blocks: impl IntoIterator<Item = u64>) -> HashMap<u64, u64> {
+        let missing = {
+            let history = self.history.lock().unwrap();
+            let mut missing = blocks
+                .into_iter()
+                .filter(|block| !history.timestamps.contains_key(block))
+                .collect::<Vec<_>>();
+            missing.sort_unstable();
+            missing.dedup();
+            missing
+        };
+        let loaded = futures::stream::iter(missing)
+            .map(|block_number| async move {
+                let block = self
+                    .eth
+                    .block(BlockId::Number(BlockNumber::Number(block_number.into())))
+                    .await;
+                (block_number, block.ok().flatten().map(|block| block.timestamp.as_u64()))
+            })
+            .buffer_unordered(MAX_PARALLEL_REQUESTS)
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut history = self.history.lock().unwrap();
+        history.timestamps.extend(
+            loaded
+                .into_iter()
+                .filter_map(|(block_number, timestamp)| Some((block_number, timestamp?))),
+        );
+        history.timestamps.clone()
+    }
+
+    /// Bid and child ids a token had at the end of each block, for transfers of tokens that don't exist anymore.
+    /// Reading past state needs an archive node, blocks that can't be read are left out.
+    pub async fn get_past_relations(
+        &self,
+        token_id: u128,
+        blocks: impl IntoIterator<Item = u64>,
+    ) -> Vec<(Vec<u128>, Vec<u128>)> {
+        let (mut relations, missing) = {
+            let history = self.history.lock().unwrap();
+            let mut relations = Vec::new();
+            let mut missing = Vec::new();
+            for block in blocks {
+                match history.relations.get(&(token_id, block)) {
+                    Some(relation) => relations.push(relation.clone()),
+                    None => missing.push(block),
+                }
+            }
+            (relations, missing)
+        };
+        let ids = |metadata: Vec<Metadata>| {
+            metadata
+                .into_iter()
+                .map(|metadata| metadata.token_id)
+                .collect::<Vec<_>>()
+        };
+        let loaded = futures::stream::iter(missing)
+            .map(|block_number| async move {
+                let block = || Some(BlockId::Number(BlockNumber::Number(block_number.into())));
+                let bids: web3::contract::Result<Vec<Metadata>> = self
+                    .contract
+                    .query("getBids", (U256::from(token_id),), None, Options::default(), block())
+                    .await;
+                let children: web3::contract::Result<Vec<Metadata>> = self
+                    .contract
+                    .query(
+                        "getChildrenMetadata",
+                        (U256::from(token_id),),
+                        None,
+                        Options::default(),
+                        block(),
+                    )
+                    .await;
+                Some((block_number, (ids(bids.ok()?), ids(children.ok()?))))
+            })
+            .buffer_unordered(MAX_PARALLEL_REQUESTS)
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut history = self.history.lock().unwrap();
+        for (block_number, relation) in loaded.into_iter().flatten() {
+            history.relations.insert((token_id, block_number), relation.clone());
+            relations.push(relation);
+        }
+        relations
+    }
+
+    /// Changes of the minimum bid of a token within `blocks`, oldest first. `setMinimumBid` emits no event, so the
+    /// minimum bid is read from past state at the ends of the range and at `known_blocks`, and the blocks where it
+    /// changes are found by bisection. The call is then decoded from the transactions of that block sent to the
+    /// contract. Reading past state needs an archive node, and a change undone before the next known block goes
+    /// unnoticed.
+    pub async fn get_minimum_price_changes(
+        &self,
+        token_id: u128,
+        blocks: RangeInclusive<u64>,
+        known_blocks: impl IntoIterator<Item = u64>,
+    ) -> Vec<MinimumPriceChange> {
+        let mut samples = known_blocks
+            .into_iter()
+            .filter(|block| blocks.contains(block))
+            // the state before the first block, so that a change in it is found too
+            .chain([blocks.start().saturating_sub(1), *blocks.end()])
+            .collect::<Vec<_>>();
+        samples.sort_unstable();
+        samples.dedup();
+
+        let mut changed = Vec::new();
+        for pair in samples.windows(2) {
+            let mut segments = vec![(pair[0], pair[1])];
+            while let Some((start, end)) = segments.pop() {
+                let start_price = self.get_past_minimum_price(token_id, start).await;
+                let end_price = self.get_past_minimum_price(token_id, end).await;
+                if start_price == end_price {
+                    continue;
+                }
+                if end == start + 1 {
+                    // the token is minted or burned where only one side has a price
+                    if let (Some(_), Some(minimum_price)) = (start_price, end_price) {
+                        changed.push((end, minimum_price));
+                    }
+                    continue;
+                }
+                let middle = start + (end - start) / 2;
+                segments.push((middle, end));
+                segments.push((start, middle));
+            }
+        }
+
+        futures::stream::iter(changed)
+            .map(|(block_number, minimum_price)| self.get_minimum_price_call(token_id, block_number, minimum_price))
+            .buffered(MAX_PARALLEL_REQUESTS)
+            .filter_map(|change| async move { change })
+            .collect()
+            .await
+    }
+
+    /// Minimum bid of a token at the end of a block, `None` when the token didn't exist or the state can't be read.
+    async fn get_past_minimum_price(&self, token_id: u128, block_number: u64) -> Option<f64> {
+        if let Some(minimum_price) = self
+            .history
+            .lock()
+            .unwrap()
+            .minimum_prices
+            .get(&(token_id, block_number))
+        {
+            return Some(*minimum_price);
+        }
+        let metadata: web3::contract::Result<Metadata> = self
+            .contract
+            .query(
+                "getMetadata",
+                (U256::from(token_id),),
+                None,
+                Options::default(),
+                Some(BlockId::Number(BlockNumber::Number(block_number.into()))),
+            )
+            .await;
+        let minimum_price = metadata.ok()?.minimum_price;
+        self.history
+            .lock()
+            .unwrap()
+            .minimum_prices
+            .insert((token_id, block_number), minimum_price);
+        Some(minimum_price)
+    }
+
+    /// The `setMinimumBid` transaction of a block that left the token at `minimum_price`.
+    async fn get_minimum_price_call(
+        &self,
+        token_id: u128,
+        block_number: u64,
+        minimum_price: f64,
+    ) -> Option<MinimumPriceChange> {
+        let block = self
+            .eth
+            .block_with_txs(BlockId::Number(BlockNumber::Number(block_number.into())))
+            .await
+            .ok()??;
+        let function = self.contract.abi().function("setMinimumBid").ok()?;
+        block.transactions.iter().rev().find_map(|transaction| {
+            if transaction.to != Some(self.contract.address()) {
+                return None;
+            }
+            let data = transaction.input.0.strip_prefix(&function.short_signature()[..])?;
+            let tokens = function.decode_input(data).ok()?;
+            let uint = |index: usize| tokens.get(index)?.clone().into_uint();
+            let price = uint(1)?.as_u128() as f64 / 10_f64.powi(18);
+            if uint(0)?.as_u128() != token_id || price != minimum_price {
+                return None;
+            }
+            Some(MinimumPriceChange {
+                tx_hash: transaction.hash,
+                block_number,
+                sender: transaction.from?,
+                minimum_price,
+            })
+        })
+    }
+
+    fn decode_transfers(&self, log: &Log) -> Vec<NftTransfer> {
+        // logs of pending blocks are picked up once they are mined
+        let (Some(tx_hash), Some(block_number)) = (log.transaction_hash, log.block_number) else {
+            return Vec::new();
+        };
+        let block_number = block_number.as_u64();
+        let raw_log = || RawLog {
+            topics: log.topics.clone(),
+            data: log.data.0.clone(),
+        };
+        let abi = self.contract.abi();
+        let param = |log: &ethabi::Log, name: &str| {
+            log.params
+                .iter()
+                .find(|param| param.name == name)
+                .map(|param| param.value.clone())
+        };
+
+        if let Some(parsed) = abi
+            .event("TransferSingle")
+            .ok()
+            .and_then(|event| event.parse_log(raw_log()).ok())
+        {
+            if let (Some(operator), Some(from), Some(to), Some(token_id)) = (
+                param(&parsed, "operator").and_then(Token::into_address),
+                param(&parsed, "from").and_then(Token::into_address),
+                param(&parsed, "to").and_then(Token::into_address),
+                param(&parsed, "id").and_then(Token::into_uint),
+            ) {
+                return vec![NftTransfer {
+                    tx_hash,
+                    block_number,
+                    operator,
+                    from,
+                    to,
+                    token_id: token_id.as_u128(),
+                }];
+            }
+        } else if let Some(parsed) = abi
+            .event("TransferBatch")
+            .ok()
+            .and_then(|event| event.parse_log(raw_log()).ok())
+        {
+            if let (Some(operator), Some(from), Some(to), Some(token_ids)) = (
+                param(&parsed, "operator").and_then(Token::into_address),
+                param(&parsed, "from").and_then(Token::into_address),
+                param(&parsed, "to").and_then(Token::into_address),
+                param(&parsed, "ids").and_then(Token::into_array),
+            ) {
+                return token_ids
+                    .into_iter()
+                    .filter_map(Token::into_uint)
+                    .map(|token_id| NftTransfer {
+                        tx_hash,
+                        block_number,
+                        operator,
+                        from,
+                        to,
+                        token_id: token_id.as_u128(),
+                    })
+                    .collect();
+            }
+        }
+        Vec::new()
+    }
 }
@@ -3,7 +3,7 @@ use std::ops::Deref;
 use ethabi::token::Token;
 use web3::{
     contract::tokens::Tokenizable,
-    types::{Address, U256},
+    types::{Address, H256, U256},
 };
 
 use mandelbrot_explorer::{BigFloat, Radix};
@@ -160,3 +160,24 @@ impl Metadata {
         }
     }
 }
+
+/// A `setMinimumBid` call that changed the minimum bid of a token.
+#[derive(Clone, Debug)]
+pub struct MinimumPriceChange {
+    pub tx_hash: H256,
+    pub block_number: u64,
+    pub sender: Address,
+    pub minimum_price: f64,
+}
+
+/// A decoded `TransferSingle` or `TransferBatch` event. Mints come from and burns go to the zero address.
+#[derive(Clone, Debug)]
+pub struct NftTransfer {
+    pub tx_hash: H256,
+    pub block_number: u64,
+    /// Account that called the contract.
+    pub operator: Address,
+    pub from: Address,
+    pub to: Address,
+    pub token_id: u128,
+}
@@ -1,9 +1,19 @@
+mod activity;
+mod address_book;
+mod archive;
 mod chain;
 mod color;
 mod components;
 mod context;
+mod coordinates;
 mod evm;
+mod notifications;
+mod palette_file;
+mod share;
+mod shortcuts;
+mod snapshot;
 mod util;
+mod watchlist;
 
 use leptos::prelude::*;
 
@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use web3::types::Address;
+
+use crate::evm::types::Metadata;
+
+const MAX_NOTIFICATIONS: usize = 100;
+
+#[derive(Clone, Copy, Deserialize, PartialEq, Serialize)]
+pub enum NotificationKind {
+    BidPlaced,
+    BidWithdrawn,
+    BidApproved,
+    /// Bid that is gone, before or without finding out whether it was approved or withdrawn.
+    BidClosed,
+}
+
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
+pub struct Notification {
+    pub kind: NotificationKind,
+    pub token_id: u128,
+    pub bid_id: u128,
+    pub owner: Address,
+    pub locked_tokens: f64,
+    pub timestamp: f64,
+    pub read: bool,
+}
+
+impl Notification {
+    fn new(kind: NotificationKind, token_id: u128, bid: &Metadata, timestamp: f64) -> Self {
+        Self {
+            kind,
+            token_id,
+            bid_id: bid.token_id,
+            owner: bid.owner,
+            locked_tokens: bid.locked_tokens,
+            timestamp,
+            read: false,
+        }
+    }
+
+    pub fn title(&self) -> String {
+        match self.kind {
+            NotificationKind::BidPlaced => format!("New bid on NFT {}", self.token_id),
+            NotificationKind::BidWithdrawn => format!("Bid withdrawn from NFT {}", self.token_id),
+            NotificationKind::BidApproved => format!("Bid approved on NFT {}", self.token_id),
+            NotificationKind::BidClosed => format!("Bid closed on NFT {}", self.token_id),
+        }
+    }
+
+    pub fn body(&self) -> String {
+        format!("Bid {} with {} OM", self.bid_id, self.locked_tokens)
+    }
+}
+
+/// Bids that were placed or closed between two snapshots of bids on owned tokens. Tokens that are not part of the
+/// previous snapshot are skipped, so that loading the inventory doesn't report every existing bid. Closed bids are
+/// reported as [`NotificationKind::BidClosed`], see [`resolve_closed`].
+pub fn diff_bids(
+    previous: &HashMap<u128, HashMap<u128, Metadata>>,
+    current: &HashMap<u128, HashMap<u128, Metadata>>,
+    timestamp: f64,
+) -> Vec<Notification> {
+    let mut notifications = Vec::new();
+    for (&token_id, current_bids) in current {
+        let Some(previous_bids) = previous.get(&token_id) else {
+            continue;
+        };
+        notifications.extend(
+            current_bids
+                .values()
+                .filter(|bid| !previous_bids.contains_key(&bid.token_id))
+                .map(|bid| Notification::new(NotificationKind::BidPlaced, token_id, bid, timestamp)),
+        );
+        notifications.extend(
+            previous_bids
+                .values()
+                .filter(|bid| !current_bids.contains_key(&bid.token_id))
+                .map(|bid| Notification::new(NotificationKind::BidClosed, token_id, bid, timestamp)),
+        );
+    }
+    notifications
+}
+
+/// Tell an approved bid from a withdrawn one: approving a bid turns it into a child token of the same id.
+pub fn resolve_closed(notification: &mut Notification, children: &[Metadata]) {
+    if notification.kind == NotificationKind::BidClosed {
+        notification.kind = if children.iter().any(|child| child.token_id == notification.bid_id) {
+            NotificationKind::BidApproved
+        } else {
+            NotificationKind::BidWithdrawn
+        };
+    }
+}
+
+/// Add new notifications in front of the existing ones, dropping the oldest ones past `MAX_NOTIFICATIONS`.
+pub fn prepend(notifications: &mut Vec<Notification>, new: Vec<Notification>) {
+    notifications.splice(0..0, new);
+    notifications.truncate(MAX_NOTIFICATIONS);
+}
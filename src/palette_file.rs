@@ -0,0 +1,669 @@
+//! Reading and writing step gradients in common gradient file formats.
+//!
+//! Positions are written in their shortest exact decimal form and colors as 8-bit channels (or as fractions of 255),
+//! so that a gradient exported here imports back with exactly the same checkpoints.
+//!
+//! The JSON format holds a whole palette, exporting a gradient alone writes just its `gradient` field:
+//!
+//! ```json
+//! {
+//!   "gradient": { "Step": { "checkpoints": [{ "position": 0.0, "color": [255, 0, 0] }] } },
+//!   "max_iterations": 40.0,
+//!   "offset": 0.0,
+//!   "length": 100.0
+//! }
+//! ```
+//!
+//! `gradient` is either `{ "Step": { "checkpoints": [...] } }`, with positions in [0, 1] and RGB colors in [0, 255],
+//! or `{ "Wave": { "red": wave, "green": wave, "blue": wave } }` where every wave is
+//! `{ "bias": f64, "amplitude": f64, "frequency": f64, "phase": f64 }`. `max_iterations` is the square root of the
+//! iteration limit, as shown on the slider. The optional `cycle_speed`, `cycle_ping_pong` and
+//! `auto_iterations: { "enabled": bool, "multiplier": f64, "min": f64, "max": f64 }` fields default to no color cycling
+//! and a fixed iteration limit.
+
+use serde::{Deserialize, Serialize};
+
+use crate::color::{Checkpoint, Gradient, Interpolation, StepGradient};
+
+// Fractint maps always have 256 entries
+const MAP_SIZE: usize = 256;
+// Checkpoints taken from maps that don't describe them
+const MAP_SAMPLING: usize = 8;
+
+const GGR_BLEND_LINEAR: u8 = 0;
+const GGR_BLEND_SINE: u8 = 2;
+const GGR_BLEND_STEP: u8 = 5;
+const GGR_COLORING_RGB: u8 = 0;
+const GGR_COLORING_HSV_CCW: u8 = 1;
+const GGR_COLORING_HSV_CW: u8 = 2;
+
+/// Palette JSON without the settings besides the gradient, see the module documentation.
+#[derive(Deserialize, Serialize)]
+struct GradientFile {
+    gradient: Gradient,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Format {
+    Json,
+    Ggr,
+    Map,
+    Css,
+}
+
+impl Format {
+    pub const ALL: [Self; 4] = [Self::Json, Self::Ggr, Self::Map, Self::Css];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Json => "JSON",
+            Self::Ggr => "GIMP (.ggr)",
+            Self::Map => "Fractint (.map)",
+            Self::Css => "CSS",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Ggr => "ggr",
+            Self::Map => "map",
+            Self::Css => "css",
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Css => "text/css",
+            Self::Ggr | Self::Map => "text/plain",
+        }
+    }
+
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        let extension = file_name.rsplit_once('.')?.1.to_lowercase();
+        Self::ALL.into_iter().find(|format| format.extension() == extension)
+    }
+}
+
+fn sorted_checkpoints(gradient: &StepGradient) -> Vec<Checkpoint> {
+    let mut checkpoints = gradient.checkpoints.clone();
+    checkpoints.sort_by(|a, b| a.position.partial_cmp(&b.position).unwrap());
+    if checkpoints.is_empty() {
+        checkpoints.push(Checkpoint {
+            position: 0.0,
+            color: [255, 255, 255],
+        });
+    }
+    checkpoints
+}
+
+/// Color where the gradient wraps around from its last checkpoint to its first one.
+fn seam_color(gradient: &StepGradient, first: &Checkpoint) -> [u8; 3] {
+    if first.position > 0.0 {
+        gradient.color_at(0.0)
+    } else {
+        first.color
+    }
+}
+
+/// Move the decimal point of a number written without an exponent, e.g. "0.333" by 2 places gives "33.3".
+fn shift_decimal_point(number: &str, places: isize) -> String {
+    let (sign, digits) = match number.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", number),
+    };
+    let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    let mut digits = format!("{}{}", integer, fraction);
+    let mut point = integer.len() as isize + places;
+    if point < 0 {
+        digits.insert_str(0, &"0".repeat(-point as usize));
+        point = 0;
+    }
+    if point as usize > digits.len() {
+        digits.push_str(&"0".repeat(point as usize - digits.len()));
+    }
+    let (integer, fraction) = digits.split_at(point as usize);
+    let integer = integer.trim_start_matches('0');
+    let fraction = fraction.trim_end_matches('0');
+    let integer = if integer.is_empty() { "0" } else { integer };
+    if fraction.is_empty() {
+        format!("{}{}", sign, integer)
+    } else {
+        format!("{}{}.{}", sign, integer, fraction)
+    }
+}
+
+fn color_hex(color: &[u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+fn parse_hex(hex: &str) -> Option<[u8; 3]> {
+    let hex = hex.strip_prefix('#')?;
+    match hex.len() {
+        6 => crate::color::hex_to_rgb(hex),
+        3 => {
+            let mut color = [0; 3];
+            for (channel, digit) in color.iter_mut().zip(hex.chars()) {
+                *channel = u8::from_str_radix(&digit.to_string(), 16).ok()? * 17;
+            }
+            Some(color)
+        }
+        _ => None,
+    }
+}
+
+pub fn export(gradient: &StepGradient, format: Format) -> String {
+    match format {
+        Format::Json => serde_json::to_string_pretty(&GradientFile {
+            gradient: gradient.clone().into(),
+        })
+        .unwrap(),
+        Format::Ggr => export_ggr(gradient),
+        Format::Map => export_map(gradient),
+        Format::Css => export_css(gradient),
+    }
+}
+
+pub fn import(text: &str, format: Format) -> Option<StepGradient> {
+    match format {
+        Format::Json => match serde_json::from_str::<GradientFile>(text) {
+            Ok(GradientFile {
+                gradient: Gradient::Step(gradient),
+            }) => Some(gradient),
+            Ok(_) => None,
+            // a bare step gradient
+            Err(_) => serde_json::from_str(text).ok(),
+        },
+        Format::Ggr => import_ggr(text),
+        Format::Map => import_map(text),
+        Format::Css => import_css(text),
+    }
+}
+
+/// GIMP blend and coloring types closest to an interpolation. OKLab and OKLCH have no counterpart, so they are
+/// written as linear RGB and named in the gradient name, which the importer reads back.
+fn ggr_blend(interpolation: Interpolation) -> (u8, u8) {
+    match interpolation {
+        Interpolation::Smoothstep => (GGR_BLEND_SINE, GGR_COLORING_RGB),
+        Interpolation::Hsv => (GGR_BLEND_LINEAR, GGR_COLORING_HSV_CCW),
+        Interpolation::Constant => (GGR_BLEND_STEP, GGR_COLORING_RGB),
+        Interpolation::Linear | Interpolation::Oklab | Interpolation::Oklch => (GGR_BLEND_LINEAR, GGR_COLORING_RGB),
+    }
+}
+
+fn ggr_interpolation(blend: u8, coloring: u8) -> Interpolation {
+    match (blend, coloring) {
+        (_, GGR_COLORING_HSV_CCW | GGR_COLORING_HSV_CW) => Interpolation::Hsv,
+        (GGR_BLEND_SINE, _) => Interpolation::Smoothstep,
+        (GGR_BLEND_STEP, _) => Interpolation::Constant,
+        _ => Interpolation::Linear,
+    }
+}
+
+fn ggr_segment(left: f64, right: f64, left_color: &[u8; 3], right_color: &[u8; 3], blend: (u8, u8)) -> String {
+    let channel = |value: u8| value as f64 / 255.0;
+    format!(
+        "{} {} {} {} {} {} 1 {} {} {} 1 {} {}",
+        left,
+        (left + right) / 2.0,
+        right,
+        channel(left_color[0]),
+        channel(left_color[1]),
+        channel(left_color[2]),
+        channel(right_color[0]),
+        channel(right_color[1]),
+        channel(right_color[2]),
+        blend.0,
+        blend.1
+    )
+}
+
+/// Every checkpoint starts a segment blended like the gradient. When the first checkpoint is past 0, the wrap around
+/// is split into two segments at the edges, which the importer recognises and skips.
+fn export_ggr(gradient: &StepGradient) -> String {
+    let checkpoints = sorted_checkpoints(gradient);
+    let blend = ggr_blend(gradient.interpolation);
+
+    let mut segments = Vec::new();
+    if let [first, .., last] = checkpoints.as_slice() {
+        let seam = seam_color(gradient, first);
+        let wrapped = first.position > 0.0;
+        if wrapped {
+            segments.push(ggr_segment(0.0, first.position, &seam, &first.color, blend));
+        }
+        for pair in checkpoints.windows(2) {
+            segments.push(ggr_segment(
+                pair[0].position,
+                pair[1].position,
+                &pair[0].color,
+                &pair[1].color,
+                blend,
+            ));
+        }
+        if wrapped {
+            segments.push(ggr_segment(last.position, 1.0, &last.color, &seam, blend));
+        } else {
+            segments.push(ggr_segment(last.position, 1.0, &last.color, &first.color, blend));
+        }
+    } else {
+        // a single checkpoint colors the whole gradient, wherever it is
+        let color = checkpoints
+            .first()
+            .map_or([255, 255, 255], |checkpoint| checkpoint.color);
+        segments.push(ggr_segment(0.0, 1.0, &color, &color, blend));
+    }
+
+    format!(
+        "GIMP Gradient\nName: Mandelbrot NFT ({})\n{}\n{}\n",
+        gradient.interpolation.label(),
+        segments.len(),
+        segments.join("\n")
+    )
+}
+
+struct GgrSegment {
+    left: f64,
+    right: f64,
+    left_color: [u8; 3],
+    right_color: [u8; 3],
+    blend: u8,
+    coloring: u8,
+}
+
+/// Segments become checkpoints at their left end. A right color that differs from the left color of the following
+/// segment adds a checkpoint at the same position, for a hard edge.
+fn import_ggr(text: &str) -> Option<StepGradient> {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+    if lines.next()? != "GIMP Gradient" {
+        return None;
+    }
+    let mut line = lines.next()?;
+    let mut named_interpolation = None;
+    if let Some(name) = line.strip_prefix("Name:") {
+        named_interpolation = Interpolation::ALL
+            .into_iter()
+            .find(|interpolation| name.trim_end().ends_with(&format!("({})", interpolation.label())));
+        line = lines.next()?;
+    }
+    let count = line.parse::<usize>().ok()?;
+    let channel = |value: &str| {
+        value
+            .parse::<f64>()
+            .ok()
+            .map(|value| (value * 255.0).round().clamp(0.0, 255.0) as u8)
+    };
+    let segments = lines
+        .take(count)
+        .map(|line| {
+            let values = line.split_whitespace().collect::<Vec<_>>();
+            if values.len() < 11 {
+                return None;
+            }
+            let number = |index: usize, default: u8| {
+                values
+                    .get(index)
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or(default)
+            };
+            Some(GgrSegment {
+                left: values[0].parse().ok()?,
+                right: values[2].parse().ok()?,
+                left_color: [channel(values[3])?, channel(values[4])?, channel(values[5])?],
+                right_color: [channel(values[7])?, channel(values[8])?, channel(values[9])?],
+                blend: number(11, GGR_BLEND_LINEAR),
+                coloring: number(12, GGR_COLORING_RGB),
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if segments.len() != count || segments.is_empty() {
+        return None;
+    }
+
+    let interpolation =
+        named_interpolation.unwrap_or_else(|| ggr_interpolation(segments[0].blend, segments[0].coloring));
+    let gradient = |segments: &[GgrSegment]| {
+        let mut checkpoints = Vec::new();
+        for (index, segment) in segments.iter().enumerate() {
+            checkpoints.push(Checkpoint {
+                position: segment.left,
+                color: segment.left_color,
+            });
+            if segments
+                .get(index + 1)
+                .is_some_and(|next| next.left_color != segment.right_color)
+            {
+                checkpoints.push(Checkpoint {
+                    position: segment.right,
+                    color: segment.right_color,
+                });
+            }
+        }
+        StepGradient {
+            checkpoints,
+            interpolation,
+        }
+    };
+
+    // a wrap around split over both edges, as written by `export_ggr`: the color at 0 is the one the gradient
+    // without the first segment has there
+    let first = segments.first().unwrap();
+    let last = segments.last().unwrap();
+    if segments.len() > 2
+        && first.left == 0.0
+        && segments[1].left > 0.0
+        && first.left_color == last.right_color
+        && first.right_color == segments[1].left_color
+    {
+        let unwrapped = gradient(&segments[1..]);
+        if unwrapped.color_at(0.0) == first.left_color {
+            return Some(unwrapped);
+        }
+    }
+    Some(gradient(&segments))
+}
+
+/// Every entry is a sample of the gradient. The entries closest to checkpoints carry them in a trailing comment, which
+/// Fractint ignores, and the first entry names the interpolation.
+fn export_map(gradient: &StepGradient) -> String {
+    let checkpoints = sorted_checkpoints(gradient);
+    let mut comments = vec![Vec::new(); MAP_SIZE];
+    comments[0].push(format!("interpolation {}", gradient.interpolation.label()));
+    for checkpoint in &checkpoints {
+        let index = ((checkpoint.position * MAP_SIZE as f64) as usize).min(MAP_SIZE - 1);
+        comments[index].push(format!(
+            "checkpoint {} {}",
+            checkpoint.position,
+            color_hex(&checkpoint.color)
+        ));
+    }
+    (0..MAP_SIZE)
+        .map(|index| {
+            let [r, g, b] = gradient.color_at(index as f64 / MAP_SIZE as f64);
+            let mut line = format!("{} {} {}", r, g, b);
+            for comment in &comments[index] {
+                line.push(' ');
+                line.push_str(comment);
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        + "\n"
+}
+
+fn import_map(text: &str) -> Option<StepGradient> {
+    let mut entries = Vec::new();
+    let mut checkpoints = Vec::new();
+    let mut interpolation = None;
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let mut words = line.split_whitespace();
+        let mut color = [0; 3];
+        for channel in color.iter_mut() {
+            *channel = words.next()?.parse().ok()?;
+        }
+        entries.push(color);
+
+        let words = words.collect::<Vec<_>>();
+        if let Some(label) = words
+            .windows(2)
+            .find(|words| words[0] == "interpolation")
+            .map(|words| words[1])
+        {
+            interpolation = Interpolation::ALL
+                .into_iter()
+                .find(|interpolation| interpolation.label() == label);
+        }
+        for comment in words.windows(3).filter(|words| words[0] == "checkpoint") {
+            checkpoints.push(Checkpoint {
+                position: comment[1].parse().ok()?,
+                color: parse_hex(comment[2])?,
+            });
+        }
+    }
+    if entries.is_empty() {
+        return None;
+    }
+
+    if checkpoints.is_empty() {
+        checkpoints = entries
+            .iter()
+            .enumerate()
+            .step_by(MAP_SAMPLING)
+            .map(|(index, &color)| Checkpoint {
+                position: index as f64 / entries.len() as f64,
+                color,
+            })
+            .collect();
+    }
+    Some(StepGradient {
+        checkpoints,
+        interpolation: interpolation.unwrap_or_default(),
+    })
+}
+
+fn export_css(gradient: &StepGradient) -> String {
+    let stops = sorted_checkpoints(gradient)
+        .iter()
+        .map(|checkpoint| {
+            format!(
+                "{} {}%",
+                color_hex(&checkpoint.color),
+                shift_decimal_point(&checkpoint.position.to_string(), 2)
+            )
+        })
+        .collect::<Vec<_>>();
+    // a single color stop is not a valid gradient
+    let stops = if stops.len() == 1 {
+        vec![stops[0].clone(), stops[0].clone()]
+    } else {
+        stops
+    };
+    format!("linear-gradient(90deg, {})\n", stops.join(", "))
+}
+
+/// Split at the commas that are not inside parentheses, e.g. between the stops of a gradient but not between the
+/// channels of `rgb(r, g, b)`.
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (index, character) in text.char_indices() {
+        match character {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(text[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(text[start..].trim());
+    parts
+}
+
+/// A CSS color at the start of a stop, and the rest of the stop. Colors are `#rgb`, `#rrggbb`, or `rgb()` and
+/// `rgba()` with channels separated by commas or spaces, the alpha channel is ignored.
+fn parse_css_color(stop: &str) -> Option<([u8; 3], &str)> {
+    if stop.starts_with('#') {
+        let (color, rest) = stop.split_once(char::is_whitespace).unwrap_or((stop, ""));
+        return Some((parse_hex(color)?, rest));
+    }
+    let arguments = stop.strip_prefix("rgba(").or_else(|| stop.strip_prefix("rgb("))?;
+    let (arguments, rest) = arguments.split_once(')')?;
+    let channels = arguments
+        .split(|character: char| character == ',' || character == '/' || character.is_whitespace())
+        .filter(|channel| !channel.is_empty())
+        .take(3)
+        .map(|channel| match channel.strip_suffix('%') {
+            Some(percent) => percent.parse::<f64>().ok().map(|percent| percent * 255.0 / 100.0),
+            None => channel.parse::<f64>().ok(),
+        })
+        .map(|channel| channel.map(|channel| channel.round().clamp(0.0, 255.0) as u8))
+        .collect::<Option<Vec<_>>>()?;
+    Some((channels.try_into().ok()?, rest))
+}
+
+fn import_css(text: &str) -> Option<StepGradient> {
+    let start = text.find("linear-gradient(")? + "linear-gradient(".len();
+    let end = start + text[start..].rfind(')')?;
+    let mut stops = Vec::new();
+    for stop in split_top_level(&text[start..end]) {
+        let Some((color, rest)) = parse_css_color(stop) else {
+            // the direction, e.g. "90deg" or "to right"
+            continue;
+        };
+        let position = match rest.split_whitespace().next() {
+            Some(position) => Some(
+                shift_decimal_point(position.strip_suffix('%')?, -2)
+                    .parse::<f64>()
+                    .ok()?,
+            ),
+            None => None,
+        };
+        stops.push((color, position));
+    }
+    if stops.is_empty() {
+        return None;
+    }
+
+    // stops without a position are spread evenly when none has one, and follow the previous stop otherwise
+    let count = stops.len();
+    let spread = stops.iter().all(|(_, position)| position.is_none());
+    let mut checkpoints = Vec::<Checkpoint>::new();
+    for (index, (color, position)) in stops.into_iter().enumerate() {
+        let position = match position {
+            Some(position) => position,
+            None if spread => index as f64 / count as f64,
+            None => checkpoints.last().map_or(0.0, |checkpoint| checkpoint.position),
+        };
+        checkpoints.push(Checkpoint { position, color });
+    }
+    checkpoints.dedup();
+    Some(StepGradient::new(checkpoints))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient(checkpoints: &[(f64, [u8; 3])], interpolation: Interpolation) -> StepGradient {
+        StepGradient {
+            checkpoints: checkpoints
+                .iter()
+                .map(|&(position, color)| Checkpoint { position, color })
+                .collect(),
+            interpolation,
+        }
+    }
+
+    fn gradients() -> Vec<StepGradient> {
+        let mut gradients = vec![
+            StepGradient::default(),
+            gradient(&[(0.0, [12, 34, 56])], Interpolation::Smoothstep),
+            // starts past 0, so that the wrap around is split over both edges
+            gradient(
+                &[(0.1, [12, 34, 56]), (0.333, [255, 0, 128]), (0.9, [1, 2, 3])],
+                Interpolation::Smoothstep,
+            ),
+            gradient(&[(0.0, [255, 0, 0]), (0.5, [0, 0, 255])], Interpolation::Smoothstep),
+            // a hard edge
+            gradient(
+                &[
+                    (0.0, [0, 0, 0]),
+                    (0.25, [0, 0, 0]),
+                    (0.25, [255, 255, 255]),
+                    (0.75, [10, 20, 30]),
+                ],
+                Interpolation::Smoothstep,
+            ),
+        ];
+        for interpolation in Interpolation::ALL {
+            gradients.push(gradient(
+                &[(0.0, [255, 0, 0]), (0.5, [0, 0, 255]), (0.8, [0, 255, 0])],
+                interpolation,
+            ));
+            gradients.push(gradient(&[(0.2, [255, 0, 0]), (0.6, [0, 0, 255])], interpolation));
+        }
+        gradients
+    }
+
+    fn sorted(gradient: &StepGradient) -> StepGradient {
+        StepGradient {
+            checkpoints: sorted_checkpoints(gradient),
+            interpolation: gradient.interpolation,
+        }
+    }
+
+    fn assert_round_trip(format: Format, keeps_interpolation: bool) {
+        for gradient in gradients() {
+            let exported = export(&gradient, format);
+            let imported = import(&exported, format).unwrap_or_else(|| panic!("unable to import {}", exported));
+            let mut expected = sorted(&gradient);
+            if !keeps_interpolation {
+                expected.interpolation = Interpolation::default();
+            }
+            assert!(sorted(&imported) == expected, "round trip changed {}", exported);
+        }
+    }
+
+    #[test]
+    fn json_round_trip() {
+        assert_round_trip(Format::Json, true);
+    }
+
+    #[test]
+    fn ggr_round_trip() {
+        assert_round_trip(Format::Ggr, true);
+    }
+
+    #[test]
+    fn map_round_trip() {
+        assert_round_trip(Format::Map, true);
+    }
+
+    #[test]
+    fn css_round_trip() {
+        assert_round_trip(Format::Css, false);
+    }
+
+    #[test]
+    fn ggr_single_checkpoint_is_one_flat_segment() {
+        for checkpoints in [&[][..], &[(0.0, [12, 34, 56])], &[(0.4, [12, 34, 56])]] {
+            let gradient = gradient(checkpoints, Interpolation::Smoothstep);
+            let exported = export(&gradient, Format::Ggr);
+            assert_eq!(exported.lines().nth(2), Some("1"), "{}", exported);
+            let imported = import(&exported, Format::Ggr).unwrap();
+            let expected = gradient
+                .checkpoints
+                .first()
+                .map_or([255, 255, 255], |checkpoint| checkpoint.color);
+            assert_eq!(imported.checkpoints.len(), 1);
+            assert_eq!(imported.checkpoints[0].color, expected);
+            assert!(imported.interpolation == gradient.interpolation);
+        }
+    }
+
+    #[test]
+    fn ggr_keeps_right_colors_and_blend() {
+        let text = "GIMP Gradient\nName: Test\n2\n0 0.25 0.5 1 0 0 1 0 0 1 1 0 0\n0.5 0.75 1 0 1 0 1 1 0 0 1 0 0\n";
+        let imported = import(text, Format::Ggr).unwrap();
+        let expected = gradient(
+            &[(0.0, [255, 0, 0]), (0.5, [0, 0, 255]), (0.5, [0, 255, 0])],
+            Interpolation::Linear,
+        );
+        assert!(imported == expected);
+    }
+
+    #[test]
+    fn css_reads_rgb_stops() {
+        let text = "background: linear-gradient(to right, rgb(255, 0, 0) 0%, rgba(0 128 255 / 50%) 50%, #fff 100%);";
+        let imported = import(text, Format::Css).unwrap();
+        let expected = gradient(
+            &[(0.0, [255, 0, 0]), (0.5, [0, 128, 255]), (1.0, [255, 255, 255])],
+            Interpolation::default(),
+        );
+        assert!(imported == expected);
+    }
+}
@@ -0,0 +1,308 @@
+//! Compact, versioned format of share links.
+//!
+//! A link carries `?share=<payload>`, the payload being URL-safe base64 without padding of:
+//!
+//! | field    | encoding                                                          |
+//! |----------|-------------------------------------------------------------------|
+//! | version  | `u8`, currently `1`                                               |
+//! | flags    | `u8`: bit 0 palette, bit 1 token id, bit 2 tab                    |
+//! | focus    | length-prefixed UTF-8, as written in `?focus=`                    |
+//! | palette  | length-prefixed palette bytes, when flagged                       |
+//! | token id | LEB128, when flagged                                              |
+//! | tab      | `u8` position of the tab, when flagged                            |
+//! | checksum | CRC-32 of every byte before it, big-endian                        |
+//!
+//! Lengths are LEB128 and floating point numbers little-endian `f32`, to keep links short. Palette values are f64, so a
+//! shared palette comes back rounded to about 7 significant digits, finer than any of its sliders or colors show.
+//! Palettes encode themselves with [`Writer`] and [`Reader`], so that their layout lives next to their fields.
+
+use std::fmt;
+
+use base64::{DecodeError, Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+
+use crate::{context::Tab, util::crc32};
+
+const VERSION: u8 = 1;
+
+const HAS_PALETTE: u8 = 1 << 0;
+const HAS_TOKEN_ID: u8 = 1 << 1;
+const HAS_TAB: u8 = 1 << 2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    Malformed,
+    UnsupportedVersion(u8),
+    Checksum,
+    Truncated,
+    Invalid,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed => write!(
+                f,
+                "The shared link is not valid, it contains characters that don't belong in it"
+            ),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "The shared link was made by a newer version of the explorer (format {}), please reload the page",
+                version
+            ),
+            Self::Checksum => write!(f, "The shared link is damaged, some of its characters have changed"),
+            Self::Truncated => write!(
+                f,
+                "The shared link is incomplete, it may have been cut short when copied"
+            ),
+            Self::Invalid => write!(f, "The shared link describes a location that can't be shown"),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Writer(Vec<u8>);
+
+impl Writer {
+    pub fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    /// Write `value` rounded to the nearest f32.
+    pub fn f32(&mut self, value: f64) {
+        self.0.extend((value as f32).to_le_bytes());
+    }
+
+    pub fn varint(&mut self, mut value: u128) {
+        loop {
+            let byte = (value & 0x7F) as u8;
+            value >>= 7;
+            if value == 0 {
+                self.0.push(byte);
+                return;
+            }
+            self.0.push(byte | 0x80);
+        }
+    }
+
+    pub fn bytes(&mut self, bytes: &[u8]) {
+        self.varint(bytes.len() as u128);
+        self.0.extend_from_slice(bytes);
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+pub struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self(bytes)
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], Error> {
+        if self.0.len() < length {
+            return Err(Error::Truncated);
+        }
+        let (taken, rest) = self.0.split_at(length);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn f32(&mut self) -> Result<f64, Error> {
+        let value = f32::from_le_bytes(self.take(4)?.try_into().unwrap());
+        if value.is_finite() {
+            Ok(value as f64)
+        } else {
+            Err(Error::Invalid)
+        }
+    }
+
+    pub fn varint(&mut self) -> Result<u128, Error> {
+        let mut value = 0u128;
+        for shift in (0..128).step_by(7) {
+            let byte = self.u8()?;
+            value |= ((byte & 0x7F) as u128).checked_shl(shift).ok_or(Error::Invalid)?;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(Error::Invalid)
+    }
+
+    pub fn bytes(&mut self) -> Result<&'a [u8], Error> {
+        let length = usize::try_from(self.varint()?).map_err(|_| Error::Invalid)?;
+        self.take(length)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Location carried by a share link.
+#[derive(Clone, PartialEq)]
+pub struct Shared {
+    pub focus: String,
+    /// Palette as encoded by the explorer.
+    pub palette: Option<Vec<u8>>,
+    pub token_id: Option<u128>,
+    pub tab: Option<Tab>,
+}
+
+pub fn encode(shared: &Shared) -> String {
+    let mut writer = Writer::default();
+    writer.u8(VERSION);
+    writer.u8(shared.palette.as_ref().map_or(0, |_| HAS_PALETTE)
+        | shared.token_id.map_or(0, |_| HAS_TOKEN_ID)
+        | shared.tab.map_or(0, |_| HAS_TAB));
+    writer.bytes(shared.focus.as_bytes());
+    if let Some(palette) = &shared.palette {
+        writer.bytes(palette);
+    }
+    if let Some(token_id) = shared.token_id {
+        writer.varint(token_id);
+    }
+    if let Some(tab) = shared.tab {
+        writer.u8(Tab::ALL.iter().position(|other| *other == tab).unwrap() as u8);
+    }
+    let mut bytes = writer.finish();
+    let checksum = crc32(&bytes);
+    bytes.extend(checksum.to_be_bytes());
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+pub fn decode(encoded: &str) -> Result<Shared, Error> {
+    let bytes = URL_SAFE_NO_PAD.decode(encoded.trim()).map_err(|error| match error {
+        // cut in the middle of a character
+        DecodeError::InvalidLength(_) | DecodeError::InvalidLastSymbol(..) => Error::Truncated,
+        _ => Error::Malformed,
+    })?;
+    let version = Reader::new(&bytes).u8()?;
+    if version != VERSION {
+        return Err(Error::UnsupportedVersion(version));
+    }
+    let (content, checksum) = bytes.split_at(bytes.len().saturating_sub(4).max(1));
+    if crc32(content).to_be_bytes() == checksum {
+        let mut reader = Reader::new(&content[1..]);
+        let shared = read_fields(&mut reader)?;
+        if !reader.is_empty() {
+            return Err(Error::Invalid);
+        }
+        return Ok(shared);
+    }
+    // without a matching checksum, a link that runs out before its fields and checksum end has been cut short,
+    // any other one has had characters changed
+    let mut reader = Reader::new(&bytes[1..]);
+    match read_fields(&mut reader) {
+        Err(Error::Truncated) => Err(Error::Truncated),
+        Ok(_) if reader.0.len() < 4 => Err(Error::Truncated),
+        _ => Err(Error::Checksum),
+    }
+}
+
+/// The fields between the version and the checksum.
+fn read_fields(reader: &mut Reader) -> Result<Shared, Error> {
+    let flags = reader.u8()?;
+    let focus = String::from_utf8(reader.bytes()?.to_vec()).map_err(|_| Error::Invalid)?;
+    let palette = if flags & HAS_PALETTE != 0 {
+        Some(reader.bytes()?.to_vec())
+    } else {
+        None
+    };
+    let token_id = if flags & HAS_TOKEN_ID != 0 {
+        Some(reader.varint()?)
+    } else {
+        None
+    };
+    let tab = if flags & HAS_TAB != 0 {
+        Some(*Tab::ALL.get(reader.u8()? as usize).ok_or(Error::Invalid)?)
+    } else {
+        None
+    };
+    Ok(Shared {
+        focus,
+        palette,
+        token_id,
+        tab,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shared() -> Shared {
+        Shared {
+            focus: "-0.75,0.1,2.5".to_string(),
+            palette: Some(vec![0, 1, 2, 250]),
+            token_id: Some(300),
+            tab: Some(Tab::ALL[1]),
+        }
+    }
+
+    fn with_checksum(mut bytes: Vec<u8>) -> String {
+        let checksum = crc32(&bytes);
+        bytes.extend(checksum.to_be_bytes());
+        URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    #[test]
+    fn round_trip() {
+        assert!(decode(&encode(&shared())) == Ok(shared()));
+        let focus_only = Shared {
+            palette: None,
+            token_id: None,
+            tab: None,
+            ..shared()
+        };
+        assert!(decode(&encode(&focus_only)) == Ok(focus_only));
+    }
+
+    #[test]
+    fn cut_link_is_truncated() {
+        let encoded = encode(&shared());
+        assert!(decode(&encoded[..4]) == Err(Error::Truncated));
+        // a token id announced by the flags but missing, behind a valid checksum
+        assert!(decode(&with_checksum(vec![VERSION, HAS_TOKEN_ID, 0])) == Err(Error::Truncated));
+    }
+
+    #[test]
+    fn link_cut_anywhere_is_truncated() {
+        let encoded = encode(&shared());
+        for length in 0..encoded.len() {
+            assert!(
+                decode(&encoded[..length]) == Err(Error::Truncated),
+                "cut after {}",
+                length
+            );
+        }
+    }
+
+    #[test]
+    fn flipped_byte_fails_checksum() {
+        let mut bytes = URL_SAFE_NO_PAD.decode(encode(&shared())).unwrap();
+        bytes[4] ^= 0x10;
+        assert!(decode(&URL_SAFE_NO_PAD.encode(bytes)) == Err(Error::Checksum));
+    }
+
+    #[test]
+    fn unknown_version_is_reported() {
+        let mut bytes = URL_SAFE_NO_PAD.decode(encode(&shared())).unwrap();
+        bytes[0] = VERSION + 1;
+        assert!(decode(&URL_SAFE_NO_PAD.encode(bytes)) == Err(Error::UnsupportedVersion(VERSION + 1)));
+    }
+
+    #[test]
+    fn floats_are_rounded_to_f32() {
+        let mut writer = Writer::default();
+        writer.f32(0.1);
+        let bytes = writer.finish();
+        assert_eq!(Reader::new(&bytes).f32(), Ok(0.1f32 as f64));
+    }
+}
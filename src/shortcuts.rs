@@ -0,0 +1,110 @@
+//! Keyboard shortcuts and their remappable key bindings.
+//!
+//! Keys are named after `KeyboardEvent.key`, lower-cased for single characters, with a `Shift+` prefix for named keys
+//! such as `Shift+Tab`. Shifted characters like `?` are already distinct keys.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Action {
+    PanLeft,
+    PanRight,
+    PanUp,
+    PanDown,
+    ZoomIn,
+    ZoomOut,
+    Parent,
+    NextFrame,
+    PreviousFrame,
+    EnterFrame,
+    ToggleBid,
+    /// Select the tab at this position, counting from 1.
+    SelectTab(u8),
+    Help,
+}
+
+impl Action {
+    pub fn label(&self) -> String {
+        match self {
+            Self::PanLeft => "Pan left".to_string(),
+            Self::PanRight => "Pan right".to_string(),
+            Self::PanUp => "Pan up".to_string(),
+            Self::PanDown => "Pan down".to_string(),
+            Self::ZoomIn => "Zoom in".to_string(),
+            Self::ZoomOut => "Zoom out".to_string(),
+            Self::Parent => "Go to the parent NFT".to_string(),
+            Self::NextFrame => "Focus the next frame".to_string(),
+            Self::PreviousFrame => "Focus the previous frame".to_string(),
+            Self::EnterFrame => "Enter the focused NFT".to_string(),
+            Self::ToggleBid => "Select or deselect the focused bid".to_string(),
+            Self::SelectTab(position) => format!("Open tab {}", position),
+            Self::Help => "Show shortcuts".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
+pub struct Binding {
+    pub action: Action,
+    pub keys: Vec<String>,
+}
+
+pub fn default_bindings() -> Vec<Binding> {
+    let binding = |action, keys: &[&str]| Binding {
+        action,
+        keys: keys.iter().map(|key| key.to_string()).collect(),
+    };
+    let mut bindings = vec![
+        binding(Action::PanLeft, &["ArrowLeft", "a"]),
+        binding(Action::PanRight, &["ArrowRight", "d"]),
+        binding(Action::PanUp, &["ArrowUp", "w"]),
+        binding(Action::PanDown, &["ArrowDown", "s"]),
+        binding(Action::ZoomIn, &["+", "="]),
+        binding(Action::ZoomOut, &["-", "_"]),
+        binding(Action::Parent, &["Backspace", "p"]),
+        binding(Action::NextFrame, &["Tab"]),
+        binding(Action::PreviousFrame, &["Shift+Tab"]),
+        binding(Action::EnterFrame, &["Enter"]),
+        binding(Action::ToggleBid, &[" "]),
+    ];
+    bindings.extend((1..=8).map(|position| binding(Action::SelectTab(position), &[&position.to_string()])));
+    bindings.push(binding(Action::Help, &["?"]));
+    bindings
+}
+
+/// Name of the key pressed, `None` when a modifier other than Shift is held, so that browser shortcuts keep working.
+pub fn key_name(ev: &web_sys::KeyboardEvent) -> Option<String> {
+    if ev.ctrl_key() || ev.alt_key() || ev.meta_key() {
+        return None;
+    }
+    let key = ev.key();
+    if matches!(key.as_str(), "Shift" | "Control" | "Alt" | "Meta") {
+        None
+    } else if key.chars().count() == 1 {
+        Some(key.to_lowercase())
+    } else if ev.shift_key() {
+        Some(format!("Shift+{}", key))
+    } else {
+        Some(key)
+    }
+}
+
+/// How a key name is shown to users.
+pub fn key_label(key: &str) -> String {
+    match key {
+        " " => "Space".to_string(),
+        "ArrowLeft" => "←".to_string(),
+        "ArrowRight" => "→".to_string(),
+        "ArrowUp" => "↑".to_string(),
+        "ArrowDown" => "↓".to_string(),
+        key if key.chars().count() == 1 => key.to_uppercase(),
+        key => key.to_string(),
+    }
+}
+
+pub fn action_for(bindings: &[Binding], key: &str) -> Option<Action> {
+    bindings
+        .iter()
+        .find(|binding| binding.keys.iter().any(|bound| bound == key))
+        .map(|binding| binding.action)
+}
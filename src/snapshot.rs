@@ -0,0 +1,382 @@
+//! Rendering views into large images, independently of the canvas size.
+//!
+//! Images are rendered in tiles by an engine of their own, on a canvas that is never shown, so they look like the
+//! explorer at any depth. The view and palette are embedded in the PNG as `tEXt` chunks under [`FOCUS_KEY`],
+//! [`PALETTE_KEY`] and [`TOKEN_ID_KEY`], which lets an exported image be opened again as a location.
+
+use std::{
+    cell::{Cell, RefCell},
+    ops::Deref,
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+use mandelbrot_explorer::{BigFloat, Bounds, Focus, ISample};
+use wasm_bindgen::JsCast;
+
+use crate::{
+    color::Gradient,
+    context::Engine,
+    coordinates::{center, width},
+    util::{crc32, next_animation_frame},
+};
+
+pub const FOCUS_KEY: &str = "Mandelbrot Focus";
+pub const PALETTE_KEY: &str = "Mandelbrot Palette";
+pub const TOKEN_ID_KEY: &str = "Mandelbrot Token";
+
+// side of the offscreen canvas that exported images are rendered on, tile by tile
+pub const TILE_SIZE: u32 = 256;
+// Browsers refuse to allocate canvases larger than this
+pub const MAX_SIDE: u32 = 16384;
+pub const MAX_AREA: u64 = 268_435_456;
+
+const PNG_SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+// Offscreen engines in existence at most, since every one of them keeps drawing
+const MAX_RENDERERS: usize = 4;
+// Side of an idle offscreen engine
+const PARKED_SIZE: u32 = 1;
+// Frames the view of an offscreen engine has to stay put before it counts as arrived and drawn
+const SETTLED_FRAMES: u32 = 3;
+// Frames to wait for a view at most, in case the engine keeps adjusting it
+const MAX_RENDER_FRAMES: u32 = 600;
+
+pub struct Resolution {
+    pub name: &'static str,
+    pub width: u32,
+    pub height: u32,
+}
+
+pub fn resolutions() -> Vec<Resolution> {
+    vec![
+        Resolution {
+            name: "Full HD",
+            width: 1920,
+            height: 1080,
+        },
+        Resolution {
+            name: "4K UHD",
+            width: 3840,
+            height: 2160,
+        },
+        Resolution {
+            name: "8K UHD",
+            width: 7680,
+            height: 4320,
+        },
+        Resolution {
+            name: "Square 8K",
+            width: 8192,
+            height: 8192,
+        },
+        Resolution {
+            name: "A4 at 300 dpi",
+            width: 3508,
+            height: 2480,
+        },
+        Resolution {
+            name: "A3 at 300 dpi",
+            width: 4961,
+            height: 3508,
+        },
+        Resolution {
+            name: "A2 at 300 dpi",
+            width: 7016,
+            height: 4961,
+        },
+    ]
+}
+
+pub fn is_supported_size(width: u32, height: u32) -> bool {
+    width > 0 && height > 0 && width <= MAX_SIDE && height <= MAX_SIDE && width as u64 * height as u64 <= MAX_AREA
+}
+
+/// Part of the complex plane covered by an image, in full precision.
+#[derive(Clone)]
+pub struct Viewport {
+    x_min: BigFloat,
+    y_max: BigFloat,
+    pixel_size: BigFloat,
+}
+
+impl Viewport {
+    /// Fit `bounds` into an image of the given size, extending the shorter side of the bounds to match its aspect
+    /// ratio.
+    pub fn new(bounds: &Bounds, image_width: u32, image_height: u32) -> Self {
+        let (x, y) = center(bounds);
+        let pixel_width = width(bounds) / BigFloat::from(image_width as f64);
+        let pixel_height = (&bounds.y_max - bounds.y_min.clone()) / BigFloat::from(image_height as f64);
+        let pixel_size = if pixel_width >= pixel_height {
+            pixel_width
+        } else {
+            pixel_height
+        };
+        Self {
+            x_min: &x - pixel_size.clone() * BigFloat::from(image_width as f64 / 2.0),
+            y_max: &y + &(pixel_size.clone() * BigFloat::from(image_height as f64 / 2.0)),
+            pixel_size,
+        }
+    }
+
+    /// Bounds of the square of `size` pixels whose top left corner is at pixel (`left`, `top`) of the image.
+    pub fn square(&self, left: u32, top: u32, size: u32) -> Bounds {
+        let offset = |pixels: u32| self.pixel_size.clone() * BigFloat::from(pixels as f64);
+        let x_min = &self.x_min + &offset(left);
+        let y_max = &self.y_max - offset(top);
+        Bounds {
+            x_max: &x_min + &offset(size),
+            y_min: &y_max - offset(size),
+            x_min,
+            y_max,
+        }
+    }
+}
+
+/// Coloring settings, as applied by the renderer.
+pub struct Coloring {
+    pub gradient: Gradient,
+    pub max_iterations: u32,
+    pub offset: f64,
+    pub length: f64,
+}
+
+impl From<&Coloring> for mandelbrot_explorer::Palette {
+    fn from(coloring: &Coloring) -> Self {
+        Self {
+            gradient: coloring.gradient.clone().into(),
+            max_iterations: coloring.max_iterations as i32,
+            offset: coloring.offset as f32,
+            length: coloring.length as f32,
+        }
+    }
+}
+
+fn same_bounds(a: &Bounds, b: &Bounds) -> bool {
+    a.x_min == b.x_min && a.x_max == b.x_max && a.y_min == b.y_min && a.y_max == b.y_max
+}
+
+/// An explorer engine drawing into a square canvas that is never shown, for rendering views other than the current
+/// one without touching it. Renderers are lent out by [`OffscreenRenderer::acquire`].
+pub struct OffscreenRenderer {
+    interface: Arc<Mutex<mandelbrot_explorer::Interface>>,
+    canvas: web_sys::HtmlCanvasElement,
+    engine: Cell<Engine>,
+    size: Cell<u32>,
+}
+
+thread_local! {
+    // a started engine can't be stopped, so renderers are reused rather than dropped
+    static RENDERERS: RefCell<Vec<Rc<OffscreenRenderer>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A renderer in use. Dropping it parks the engine at a single pixel, so that an idle engine costs next to nothing
+/// on the animation frames it keeps drawing.
+pub struct RendererLease(Rc<OffscreenRenderer>);
+
+impl Deref for RendererLease {
+    type Target = OffscreenRenderer;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Drop for RendererLease {
+    fn drop(&mut self) {
+        self.0.resize(PARKED_SIZE);
+    }
+}
+
+impl OffscreenRenderer {
+    /// A renderer nobody else is using, moved to the given engine, size and coloring. `None` once
+    /// [`MAX_RENDERERS`] are in use.
+    pub fn acquire(engine: Engine, size: u32, coloring: &Coloring) -> Option<RendererLease> {
+        let idle = RENDERERS.with_borrow(|renderers| {
+            let idle = || renderers.iter().filter(|renderer| Rc::strong_count(renderer) == 1);
+            idle()
+                .find(|renderer| renderer.engine.get() == engine)
+                .or_else(|| idle().next())
+                .cloned()
+        });
+        let renderer = match idle {
+            Some(renderer) => renderer,
+            None if RENDERERS.with_borrow(Vec::len) < MAX_RENDERERS => {
+                let renderer = Rc::new(Self::new(engine, coloring)?);
+                RENDERERS.with_borrow_mut(|renderers| renderers.push(renderer.clone()));
+                renderer
+            }
+            None => return None,
+        };
+        if renderer.engine.get() != engine {
+            renderer.set_engine(engine);
+        }
+        renderer.resize(size);
+        renderer.set_coloring(coloring);
+        Some(RendererLease(renderer))
+    }
+
+    /// A parked renderer.
+    fn new(engine: Engine, coloring: &Coloring) -> Option<Self> {
+        let canvas = web_sys::window()?
+            .document()?
+            .create_element("canvas")
+            .ok()?
+            .dyn_into::<web_sys::HtmlCanvasElement>()
+            .ok()?;
+        canvas.set_width(PARKED_SIZE);
+        canvas.set_height(PARKED_SIZE);
+        // the view of an offscreen engine is not part of the browser history
+        let interface = Arc::new(Mutex::new(mandelbrot_explorer::Interface::new(
+            Rc::new(RefCell::new(match engine {
+                Engine::Perturbation => mandelbrot_explorer::Perturbation::new(PARKED_SIZE, PARKED_SIZE, |_| {}).into(),
+                Engine::Optimised => mandelbrot_explorer::Optimised::new(PARKED_SIZE, PARKED_SIZE, |_| {}).into(),
+            })),
+            coloring.into(),
+        )));
+        mandelbrot_explorer::start(Some(canvas.clone()), interface.clone(), Arc::new(|| {}));
+        Some(Self {
+            interface,
+            canvas,
+            engine: Cell::new(engine),
+            size: Cell::new(PARKED_SIZE),
+        })
+    }
+
+    /// Swap the engine in place, at the current size.
+    fn set_engine(&self, engine: Engine) {
+        let size = self.size.get();
+        *self.interface.lock().unwrap().engine.borrow_mut() = match engine {
+            Engine::Perturbation => mandelbrot_explorer::Perturbation::new(size, size, |_| {}).into(),
+            Engine::Optimised => mandelbrot_explorer::Optimised::new(size, size, |_| {}).into(),
+        };
+        self.engine.set(engine);
+    }
+
+    fn resize(&self, size: u32) {
+        if self.size.replace(size) != size {
+            self.canvas.set_width(size);
+            self.canvas.set_height(size);
+            self.interface.lock().unwrap().engine.borrow_mut().resize(size, size);
+        }
+    }
+
+    /// Side of the rendered square in pixels.
+    pub fn size(&self) -> u32 {
+        self.size.get()
+    }
+
+    pub fn set_coloring(&self, coloring: &Coloring) {
+        self.interface.lock().unwrap().palette = coloring.into();
+    }
+
+    /// Render square bounds, the canvas holds the image until the next rendering.
+    pub async fn render_bounds(&self, bounds: &Bounds) -> &web_sys::HtmlCanvasElement {
+        self.interface.lock().unwrap().move_into_bounds(bounds);
+        self.settle().await
+    }
+
+    /// Bounds a canvas of side `size` shows for `focus`. The engine that finds them is given back right away, so
+    /// one engine serves every size instead of each canvas size keeping one of its own.
+    pub async fn locate(engine: Engine, size: u32, focus: Focus, coloring: &Coloring) -> Option<Bounds> {
+        Some(Self::acquire(engine, size, coloring)?.focus_bounds(focus).await)
+    }
+
+    /// Bounds an engine of this size shows for `focus`.
+    pub async fn focus_bounds(&self, focus: Focus) -> Bounds {
+        self.interface.lock().unwrap().move_into_focus(focus);
+        self.settle().await;
+        self.bounds()
+    }
+
+    /// Bounds the engine has moved to.
+    fn bounds(&self) -> Bounds {
+        self.interface.lock().unwrap().engine.borrow().get_bounds()
+    }
+
+    /// Wait until the engine has arrived at its view and drawn it.
+    async fn settle(&self) -> &web_sys::HtmlCanvasElement {
+        if let Some(redraw) = &self.interface.lock().unwrap().redraw {
+            redraw();
+        }
+        let mut bounds = self.bounds();
+        let mut settled_frames = 0;
+        for _ in 0..MAX_RENDER_FRAMES {
+            next_animation_frame().await;
+            let current = self.bounds();
+            settled_frames = if same_bounds(&current, &bounds) {
+                settled_frames + 1
+            } else {
+                0
+            };
+            if settled_frames >= SETTLED_FRAMES {
+                break;
+            }
+            bounds = current;
+        }
+        &self.canvas
+    }
+}
+
+/// Square tiles of side `size` covering an image, as (left, top, width, height) of the part inside the image, row
+/// by row.
+pub fn tiles(width: u32, height: u32, size: u32) -> Vec<(u32, u32, u32, u32)> {
+    (0..height)
+        .step_by(size as usize)
+        .flat_map(|top| {
+            (0..width)
+                .step_by(size as usize)
+                .map(move |left| (left, top, size.min(width - left), size.min(height - top)))
+        })
+        .collect()
+}
+
+/// Split a PNG file into (type, data) chunks.
+fn chunks(png: &[u8]) -> Option<Vec<([u8; 4], &[u8])>> {
+    let mut rest = png.strip_prefix(&PNG_SIGNATURE)?;
+    let mut chunks = Vec::new();
+    while !rest.is_empty() {
+        let length = u32::from_be_bytes(rest.get(0..4)?.try_into().ok()?) as usize;
+        let chunk_type = rest.get(4..8)?.try_into().ok()?;
+        chunks.push((chunk_type, rest.get(8..8 + length)?));
+        // skip the CRC
+        rest = rest.get(12 + length..)?;
+    }
+    Some(chunks)
+}
+
+/// Add `tEXt` chunks with the given keywords and texts right after the header of a PNG file.
+pub fn with_text_chunks(png: &[u8], texts: &[(&str, String)]) -> Option<Vec<u8>> {
+    let chunks = chunks(png)?;
+    let mut output = PNG_SIGNATURE.to_vec();
+    let mut write_chunk = |chunk_type: &[u8; 4], data: &[u8]| {
+        output.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        let start = output.len();
+        output.extend_from_slice(chunk_type);
+        output.extend_from_slice(data);
+        let crc = crc32(&output[start..]);
+        output.extend_from_slice(&crc.to_be_bytes());
+    };
+    for (chunk_type, data) in chunks {
+        write_chunk(&chunk_type, data);
+        if &chunk_type == b"IHDR" {
+            for (keyword, text) in texts {
+                let mut data = keyword.as_bytes().to_vec();
+                data.push(0);
+                data.extend_from_slice(text.as_bytes());
+                write_chunk(b"tEXt", &data);
+            }
+        }
+    }
+    Some(output)
+}
+
+/// Read the `tEXt` chunk with the given keyword from a PNG file.
+pub fn text_chunk(png: &[u8], keyword: &str) -> Option<String> {
+    chunks(png)?
+        .into_iter()
+        .filter(|(chunk_type, _)| chunk_type == b"tEXt")
+        .find_map(|(_, data)| {
+            let (chunk_keyword, text) = data.split_at(data.iter().position(|byte| *byte == 0)?);
+            (chunk_keyword == keyword.as_bytes()).then(|| String::from_utf8_lossy(&text[1..]).into_owned())
+        })
+}
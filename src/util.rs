@@ -1,6 +1,9 @@
+use std::time::Duration;
+
 use leptos::prelude::*;
 use leptos_router::params::ParamsMap;
 use serde::{Serialize, de::DeserializeOwned};
+use wasm_bindgen::{JsCast, JsValue};
 use web_sys::window;
 
 pub fn store_item(key: &str, value: &impl Serialize) {
@@ -16,6 +19,22 @@ where
     serde_json::from_str(&window()?.local_storage().ok()??.get_item(key).ok()??).ok()
 }
 
+/// CRC-32 as used by PNG chunks, ZIP archives and share links.
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
 /// Parse the query string as returned by `web_sys::window()?.location().search()?` and get a
 /// specific key out of it.
 pub fn parse_url_query_string<'a>(query: &'a str, search_key: &str) -> Option<&'a str> {
@@ -45,3 +64,70 @@ pub fn preserve_log_level(uri: String, query_map: Memo<ParamsMap>) -> String {
         uri
     }
 }
+
+/// Format milliseconds since the Unix epoch as a local date and time.
+pub fn format_timestamp(timestamp: f64) -> String {
+    js_sys::Date::new(&JsValue::from_f64(timestamp))
+        .to_locale_string("default", &JsValue::UNDEFINED)
+        .into()
+}
+
+/// Let the browser download `bytes` as a file with the given name.
+pub fn download_file(file_name: &str, mime_type: &str, bytes: &[u8]) {
+    download_parts(
+        file_name,
+        mime_type,
+        &js_sys::Array::of1(&js_sys::Uint8Array::from(bytes)),
+    );
+}
+
+/// Download a file made of parts that have already been copied out of wasm memory, e.g. as `Uint8Array`s.
+pub fn download_parts(file_name: &str, mime_type: &str, parts: &js_sys::Array) {
+    let Some(document) = window().and_then(|w| w.document()) else {
+        return;
+    };
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(mime_type);
+    let Ok(blob) = web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options) else {
+        return;
+    };
+    let Ok(url) = web_sys::Url::create_object_url_with_blob(&blob) else {
+        return;
+    };
+    if let Ok(anchor) = document
+        .create_element("a")
+        .map(|element| element.unchecked_into::<web_sys::HtmlAnchorElement>())
+    {
+        anchor.set_href(&url);
+        anchor.set_download(file_name);
+        anchor.click();
+    }
+    set_timeout(
+        move || {
+            let _ = web_sys::Url::revoke_object_url(&url);
+        },
+        Duration::from_secs(1),
+    );
+}
+
+pub async fn read_file_text(file: &web_sys::File) -> Option<String> {
+    wasm_bindgen_futures::JsFuture::from(file.text())
+        .await
+        .ok()?
+        .as_string()
+}
+
+pub async fn read_file_bytes(file: &web_sys::File) -> Option<Vec<u8>> {
+    let buffer = wasm_bindgen_futures::JsFuture::from(file.array_buffer()).await.ok()?;
+    Some(js_sys::Uint8Array::new(&buffer).to_vec())
+}
+
+/// Wait for the next animation frame, the explorer draws in animation frames.
+pub async fn next_animation_frame() {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        if let Some(window) = window() {
+            let _ = window.request_animation_frame(&resolve);
+        }
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
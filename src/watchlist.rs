@@ -0,0 +1,171 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use web3::types::Address;
+
+use crate::{address_book::shorten, evm::types::Metadata};
+
+#[derive(Clone, Default, Deserialize, PartialEq, Serialize)]
+pub struct Watchlist {
+    pub tokens: BTreeSet<u128>,
+    pub addresses: BTreeSet<Address>,
+}
+
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
+pub struct TokenSnapshot {
+    pub owner: Address,
+    pub minimum_price: f64,
+    pub bids: BTreeSet<u128>,
+    pub children: BTreeSet<u128>,
+}
+
+impl TokenSnapshot {
+    pub fn new(token: &Metadata, bids: &[Metadata], children: &[Metadata]) -> Self {
+        Self {
+            owner: token.owner,
+            minimum_price: token.minimum_price,
+            bids: bids.iter().map(|bid| bid.token_id).collect(),
+            children: children.iter().map(|child| child.token_id).collect(),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
+pub struct AddressSnapshot {
+    pub tokens: BTreeSet<u128>,
+    pub bids: BTreeSet<u128>,
+}
+
+impl AddressSnapshot {
+    pub fn new(tokens: &[Metadata], bids: &[Metadata]) -> Self {
+        Self {
+            tokens: tokens.iter().map(|token| token.token_id).collect(),
+            bids: bids.iter().map(|bid| bid.token_id).collect(),
+        }
+    }
+}
+
+#[derive(Clone, Default, Deserialize, PartialEq, Serialize)]
+pub struct Snapshot {
+    pub tokens: BTreeMap<u128, TokenSnapshot>,
+    pub addresses: BTreeMap<Address, AddressSnapshot>,
+}
+
+#[derive(Clone, PartialEq)]
+pub enum Change {
+    BidPlaced { token_id: u128, bid_id: u128 },
+    BidRemoved { token_id: u128, bid_id: u128 },
+    ChildMinted { token_id: u128, child_id: u128 },
+    ChildBurned { token_id: u128, child_id: u128 },
+    OwnerChanged { token_id: u128, from: Address, to: Address },
+    MinimumPriceChanged { token_id: u128, from: f64, to: f64 },
+    TokenAcquired { address: Address, token_id: u128 },
+    TokenLost { address: Address, token_id: u128 },
+    BidCreated { address: Address, bid_id: u128 },
+    BidClosed { address: Address, bid_id: u128 },
+}
+
+impl Change {
+    pub fn token_id(&self) -> Option<u128> {
+        match self {
+            Self::BidPlaced { token_id, .. }
+            | Self::BidRemoved { token_id, .. }
+            | Self::ChildMinted { token_id, .. }
+            | Self::ChildBurned { token_id, .. }
+            | Self::OwnerChanged { token_id, .. }
+            | Self::MinimumPriceChanged { token_id, .. } => Some(*token_id),
+            _ => None,
+        }
+    }
+
+    pub fn address(&self) -> Option<Address> {
+        match self {
+            Self::TokenAcquired { address, .. }
+            | Self::TokenLost { address, .. }
+            | Self::BidCreated { address, .. }
+            | Self::BidClosed { address, .. } => Some(*address),
+            _ => None,
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Self::BidPlaced { bid_id, .. } => format!("New bid {}", bid_id),
+            Self::BidRemoved { bid_id, .. } => format!("Bid {} was approved or withdrawn", bid_id),
+            Self::ChildMinted { child_id, .. } => format!("NFT {} was minted inside", child_id),
+            Self::ChildBurned { child_id, .. } => format!("NFT {} inside was burned", child_id),
+            Self::OwnerChanged { from, to, .. } => format!("Owner changed from {} to {}", shorten(from), shorten(to)),
+            Self::MinimumPriceChanged { from, to, .. } => format!("Minimum bid changed from {} to {}", from, to),
+            Self::TokenAcquired { token_id, .. } => format!("Now owns NFT {}", token_id),
+            Self::TokenLost { token_id, .. } => format!("No longer owns NFT {}", token_id),
+            Self::BidCreated { bid_id, .. } => format!("Placed bid {}", bid_id),
+            Self::BidClosed { bid_id, .. } => format!("Bid {} was approved or withdrawn", bid_id),
+        }
+    }
+}
+
+fn diff_ids<'a>(
+    previous: &'a BTreeSet<u128>,
+    current: &'a BTreeSet<u128>,
+) -> (impl Iterator<Item = u128> + 'a, impl Iterator<Item = u128> + 'a) {
+    (
+        current.difference(previous).copied(),
+        previous.difference(current).copied(),
+    )
+}
+
+impl Snapshot {
+    /// Changes between this snapshot and a more recent one. Items that are missing from either snapshot are
+    /// skipped, as there is nothing to compare them with.
+    pub fn diff(&self, current: &Snapshot) -> Vec<Change> {
+        let mut changes = Vec::new();
+        for (&token_id, previous) in &self.tokens {
+            let Some(current) = current.tokens.get(&token_id) else {
+                continue;
+            };
+            if previous.owner != current.owner {
+                changes.push(Change::OwnerChanged {
+                    token_id,
+                    from: previous.owner,
+                    to: current.owner,
+                });
+            }
+            if previous.minimum_price != current.minimum_price {
+                changes.push(Change::MinimumPriceChanged {
+                    token_id,
+                    from: previous.minimum_price,
+                    to: current.minimum_price,
+                });
+            }
+            let (placed, removed) = diff_ids(&previous.bids, &current.bids);
+            changes.extend(placed.map(|bid_id| Change::BidPlaced { token_id, bid_id }));
+            changes.extend(removed.map(|bid_id| Change::BidRemoved { token_id, bid_id }));
+            let (minted, burned) = diff_ids(&previous.children, &current.children);
+            changes.extend(minted.map(|child_id| Change::ChildMinted { token_id, child_id }));
+            changes.extend(burned.map(|child_id| Change::ChildBurned { token_id, child_id }));
+        }
+        for (&address, previous) in &self.addresses {
+            let Some(current) = current.addresses.get(&address) else {
+                continue;
+            };
+            let (acquired, lost) = diff_ids(&previous.tokens, &current.tokens);
+            changes.extend(acquired.map(|token_id| Change::TokenAcquired { address, token_id }));
+            changes.extend(lost.map(|token_id| Change::TokenLost { address, token_id }));
+            let (created, closed) = diff_ids(&previous.bids, &current.bids);
+            changes.extend(created.map(|bid_id| Change::BidCreated { address, bid_id }));
+            changes.extend(closed.map(|bid_id| Change::BidClosed { address, bid_id }));
+        }
+        changes
+    }
+
+    /// Take over snapshots of items that were not part of this snapshot yet, so that newly watched items start
+    /// from their current state.
+    pub fn extend_missing(&mut self, current: &Snapshot) {
+        for (token_id, snapshot) in &current.tokens {
+            self.tokens.entry(*token_id).or_insert_with(|| snapshot.clone());
+        }
+        for (address, snapshot) in &current.addresses {
+            self.addresses.entry(*address).or_insert_with(|| snapshot.clone());
+        }
+    }
+}